@@ -0,0 +1,85 @@
+// 指し手生成・着手・評価関数・固定深さのアルファベータ探索の速度を計測するベンチマーク
+//
+// 実行: cargo bench --features bench --bench engine（特定の項目だけなら末尾に -- alpha_beta）
+// 序盤・中盤・終盤の局面ごとに計測し、前回の結果との差を Criterion が表示する。
+// Criterion はネイティブでのみ使えるため、wasm32 向けには空の main だけを置く。
+
+#[cfg(not(target_arch = "wasm32"))]
+mod engine {
+    use criterion::{black_box, criterion_group, BatchSize, BenchmarkId, Criterion};
+    use mini_wasm_othello::bench::{Engine, Position, INFINITY};
+
+    // (名前, 局面文字列, alpha_beta の深さ)。同じ対局の 6 手目・26 手目・48 手目
+    const POSITIONS: &[(&str, &str, u8)] = &[
+        ("opening", "-------------------XO------OO-----OXO------X------OX------------ X", 5),
+        ("midgame", "-------------------XXXOO-XXXXOO---XXOXOO--OOOXOX--OOOOX---OO---- X", 5),
+        ("endgame", "-X-O-X-OO-XOOXO-OXXXOOXOOXOOOXXXOOXOOXXXOXOXXOXXO-OOOOOX--OO---O X", 6),
+    ];
+
+    fn positions() -> impl Iterator<Item = (&'static str, Position, u8)> {
+        POSITIONS.iter().map(|&(name, position, depth)| (name, Position::from_position(position).unwrap(), depth))
+    }
+
+    fn get_valid_moves_for_board(c: &mut Criterion) {
+        let mut group = c.benchmark_group("get_valid_moves_for_board");
+        for (name, position, _) in positions() {
+            group.bench_with_input(BenchmarkId::from_parameter(name), &position, |b, position| {
+                b.iter(|| black_box(position).get_valid_moves_for_board(position.player()))
+            });
+        }
+        group.finish();
+    }
+
+    fn make_move_on_board(c: &mut Criterion) {
+        let mut group = c.benchmark_group("make_move_on_board");
+        for (name, position, _) in positions() {
+            // 局面の有効手をすべて1回ずつ打つ
+            let moves = position.get_valid_moves_for_board(position.player());
+            group.bench_with_input(BenchmarkId::from_parameter(name), &position, |b, position| {
+                b.iter(|| {
+                    for &(row, col) in &moves {
+                        let mut next = position.clone();
+                        next.make_move_on_board(row, col, position.player());
+                        black_box(next);
+                    }
+                })
+            });
+        }
+        group.finish();
+    }
+
+    fn evaluate_board(c: &mut Criterion) {
+        let mut group = c.benchmark_group("evaluate_board");
+        for (name, position, _) in positions() {
+            let engine = Engine::new(position.player());
+            group.bench_with_input(BenchmarkId::from_parameter(name), &position, |b, position| {
+                b.iter(|| engine.evaluate_board(black_box(position), position.player()))
+            });
+        }
+        group.finish();
+    }
+
+    fn alpha_beta(c: &mut Criterion) {
+        let mut group = c.benchmark_group("alpha_beta");
+        group.sample_size(10);
+        for (name, position, depth) in positions() {
+            // 置換表に前回の結果が残らないよう、毎回新しい探索で計測する
+            group.bench_with_input(BenchmarkId::new(name, format!("depth {}", depth)), &position, |b, position| {
+                b.iter_batched(
+                    || Engine::new(position.player()),
+                    |engine| engine.alpha_beta(position, depth, -INFINITY, INFINITY, position.player()),
+                    BatchSize::SmallInput,
+                )
+            });
+        }
+        group.finish();
+    }
+
+    criterion_group!(benches, get_valid_moves_for_board, make_move_on_board, evaluate_board, alpha_beta);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+criterion::criterion_main!(engine::benches);
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
@@ -0,0 +1,60 @@
+// 強さの自動調整で着手の精度を求める尺度（adaptive.rs の LOSS_SCALE）を当てはめ直すツール
+//
+// 使い方: cargo run --release --example loss_scale [開始局面数]
+// tournament と同じ拮抗した開始局面から初級（貪欲法）同士で対局させ、各手の損失（深さ 4 の最善手との石数の差）から、
+// 精度 exp(-損失 / LOSS_SCALE) の平均がいつもの精度の初期値 0.5 になる尺度を二分法で求める。既定は 8 局面。
+
+use std::process::ExitCode;
+
+use mini_wasm_othello::review::eval_to_discs;
+use mini_wasm_othello::tournament;
+use mini_wasm_othello::{AiConfig, AiDifficulty, Strategy};
+
+const USAGE: &str = "使い方: loss_scale [開始局面数]";
+
+// 着手の精度を判定する探索の深さ（adaptive.rs の JUDGE_DEPTH）
+const JUDGE_DEPTH: u8 = 4;
+
+fn main() -> ExitCode {
+    let openings = match std::env::args().nth(1).map(|arg| arg.parse::<usize>()) {
+        None => 8,
+        Some(Ok(count)) if count > 0 => count,
+        Some(_) => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let easy = AiConfig::from(AiDifficulty::Easy);
+    let mut losses = Vec::new();
+    for mut game in tournament::balanced_openings(6, openings) {
+        while let Some((row, col)) = game.ai_move_with(easy) {
+            let evaluations = game.evaluate_moves_with(Strategy::AlphaBeta(JUDGE_DEPTH));
+            let best = evaluations.iter().map(|&(_, score)| score).max();
+            let played = evaluations.iter().find(|&&(square, _)| square == (row, col)).map(|&(_, score)| score);
+            if let (true, Some(best), Some(played)) = (evaluations.len() >= 2, best, played) {
+                losses.push((eval_to_discs(best) - eval_to_discs(played)).max(0) as f64);
+            }
+            game.play(row, col).expect("AI の手は有効");
+        }
+    }
+    if losses.is_empty() {
+        eprintln!("精度を測れる手がありません");
+        return ExitCode::FAILURE;
+    }
+
+    // 尺度が大きいほど精度の平均は高い
+    let mean_accuracy = |scale: f64| losses.iter().map(|loss| (-loss / scale).exp()).sum::<f64>() / losses.len() as f64;
+    let (mut low, mut high) = (0.1, 100.0);
+    for _ in 0..50 {
+        let middle = (low + high) / 2.0;
+        if mean_accuracy(middle) < 0.5 {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    println!("{} 手", losses.len());
+    println!("LOSS_SCALE {:.2}", low);
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,75 @@
+// 振り返りで評価値を石数・勝率に換算する尺度（review.rs の EVAL_PER_DISC と WIN_SCALE）を当てはめ直すツール
+//
+// 使い方: cargo run --release --example review_scales [局面数]
+// 中級同士の対局（seed ごとに手を変える）から空きマス 14〜15 の局面を取り、深さ 5 の最善手の評価値を
+// 完全読みの石差に最小二乗法（原点を通る直線）で当てはめる。WIN_SCALE は、その換算で求めた予想石差と
+// 完全読みの勝敗（引き分けは 0.5）から最尤推定する（0.1 刻み）。既定は 200 局面（1 コアで 1 分ほど）。
+
+use std::process::ExitCode;
+
+use mini_wasm_othello::endgame::Solver;
+use mini_wasm_othello::review::ReviewOptions;
+use mini_wasm_othello::{AiConfig, AiDifficulty, Game, Strategy};
+
+const USAGE: &str = "使い方: review_scales [局面数]";
+
+// 空きマス 14〜15 の局面の (振り返りと同じ深さの最善手の評価値, 完全読みの石差)
+fn endgame_samples(count: u64) -> Vec<(i32, i32)> {
+    let mut samples = Vec::new();
+    for seed in 0..count {
+        let config = AiConfig::from(AiDifficulty::Medium).with_variety(seed);
+        let mut game = Game::new();
+        while game.board().iter().flatten().filter(|&&cell| cell == 0).count() > 15 {
+            match game.ai_move_with(config) {
+                Some((row, col)) => game.play(row, col).expect("AI の手は有効"),
+                None => break,
+            }
+        }
+        if game.is_game_over() || game.valid_moves().is_empty() {
+            continue;
+        }
+        let Some(eval) = game.evaluate_moves_with(Strategy::AlphaBeta(ReviewOptions::default().depth)).into_iter().map(|(_, value)| value).max() else {
+            continue;
+        };
+        samples.push((eval, Solver::new().solve(&game).score));
+    }
+    samples
+}
+
+fn main() -> ExitCode {
+    let count = match std::env::args().nth(1).map(|arg| arg.parse::<u64>()) {
+        None => 200,
+        Some(Ok(count)) if count > 0 => count,
+        Some(_) => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let samples = endgame_samples(count);
+    let (sum_ed, sum_dd) = samples.iter().fold((0.0, 0.0), |(ed, dd), &(eval, discs)| {
+        (ed + eval as f64 * discs as f64, dd + discs as f64 * discs as f64)
+    });
+    let eval_per_disc = sum_ed / sum_dd;
+
+    let log_likelihood = |scale: f64| -> f64 {
+        samples
+            .iter()
+            .map(|&(eval, discs)| {
+                let advantage = (eval as f64 / eval_per_disc).round().clamp(-64.0, 64.0);
+                let probability = 1.0 / (1.0 + (-advantage / scale).exp());
+                let outcome = (discs.signum() as f64 + 1.0) / 2.0;
+                outcome * probability.max(1e-9).ln() + (1.0 - outcome) * (1.0 - probability).max(1e-9).ln()
+            })
+            .sum()
+    };
+    let win_scale = (10..=300)
+        .map(|tenths| tenths as f64 / 10.0)
+        .max_by(|&a, &b| log_likelihood(a).total_cmp(&log_likelihood(b)))
+        .unwrap_or(1.0);
+
+    println!("{} 局面", samples.len());
+    println!("EVAL_PER_DISC {:.1}", eval_per_disc);
+    println!("WIN_SCALE {:.1}", win_scale);
+    ExitCode::SUCCESS
+}
@@ -1,4 +1,4 @@
-import init, { OthelloGame } from './pkg/mini_wasm_othello.js';
+import init, { OthelloGame, IncrementalSearch, AdaptiveDifficulty } from './pkg/mini_wasm_othello.js';
 
 let game;
 let gameInProgress = true;
@@ -37,9 +37,137 @@ async function run() {
     // ウィンドウリサイズ時にも対応
     window.addEventListener('resize', resizeCanvas);
     
+    // AIの探索は Web Worker で行い、探索中もメインスレッドが描画を続けられるようにする
+    // Worker が使えない環境では、メインスレッドで描画の合間に少しずつ探索する
+    let aiWorker = null;
+    try {
+        aiWorker = new Worker('./worker.js', { type: 'module' });
+    } catch (error) {
+        console.warn('Web Worker を利用できないため、メインスレッドで探索します:', error);
+    }
+    let searchId = 0;
+    let pendingSearch = null;
+    
+    // メインスレッドで1フレームあたりに探索するノード数
+    const NODES_PER_FRAME = 3000;
+    
+    function searchOnMainThread(id, position, config) {
+        const search = IncrementalSearch.with_config(position, config);
+        pendingSearch.search = search;
+        
+        function stepSearch() {
+            // キャンセルされた、または新しい探索が始まった
+            if (!pendingSearch || pendingSearch.id !== id) {
+                search.free();
+                return;
+            }
+            
+            if (search.step(NODES_PER_FRAME)) {
+                const move = Array.from(search.best_move());
+                search.free();
+                const { resolve } = pendingSearch;
+                pendingSearch = null;
+                resolve(move);
+            } else {
+                requestAnimationFrame(stepSearch);
+            }
+        }
+        requestAnimationFrame(stepSearch);
+    }
+    
+    aiWorker?.addEventListener('error', (event) => {
+        // モジュール Worker に未対応のブラウザなど。実行中の探索はメインスレッドでやり直す
+        console.warn('Web Worker でエラーが発生したため、メインスレッドで探索します:', event.message);
+        aiWorker = null;
+        if (pendingSearch && !pendingSearch.search) {
+            searchOnMainThread(pendingSearch.id, pendingSearch.position, pendingSearch.config);
+        }
+    });
+    
+    aiWorker?.addEventListener('message', (event) => {
+        const message = event.data;
+        // キャンセル済み・古い探索の結果は無視
+        if (!pendingSearch || message.id !== pendingSearch.id) {
+            return;
+        }
+        
+        const { resolve } = pendingSearch;
+        pendingSearch = null;
+        if (message.type === 'error') {
+            console.error('AIの探索に失敗しました:', message.error);
+        }
+        resolve(message.type === 'result' ? message.move : null);
+    });
+    
+    // 現在の局面のAIの手を Worker に探索させる（キャンセルされた場合は null）
+    function requestAiMove() {
+        cancelAiSearch();
+        return new Promise((resolve) => {
+            const id = ++searchId;
+            const position = game.export_position();
+            const config = game.ai_config;
+            pendingSearch = { id, resolve, position, config, search: null };
+            
+            if (aiWorker) {
+                aiWorker.postMessage({ type: 'search', id, position, config });
+            } else {
+                searchOnMainThread(id, position, config);
+            }
+        });
+    }
+    
+    // 実行中のAIの探索をキャンセル
+    function cancelAiSearch() {
+        if (pendingSearch) {
+            if (pendingSearch.search) {
+                pendingSearch.search.cancel();
+            } else if (aiWorker) {
+                aiWorker.postMessage({ type: 'cancel' });
+            }
+            pendingSearch.resolve(null);
+            pendingSearch = null;
+        }
+    }
+    
+    // マスの名前（例: "d3"）
+    function squareName(row, col) {
+        return 'abcdefgh'[col] + (row + 1);
+    }
+    
+    // AIの手番を実行
+    async function playAiTurn() {
+        const currentGame = game;
+        document.getElementById('current-player').textContent += ' 思考中…';
+        
+        const move = await requestAiMove();
+        // 探索中に新しいゲームが始まった場合などは何もしない
+        if (move === null || currentGame !== game || !gameInProgress || game.current_player === playerColor) {
+            return;
+        }
+        
+        // 打つ前の局面で AI の手の理由を説明する
+        document.getElementById('ai-explanation').textContent =
+            move[0] >= 0 ? `AI の手 (${squareName(move[0], move[1])}): ${game.explain_move(move[0], move[1], 'ja')}` : '';
+        const aiMoved = move[0] >= 0 && game.play_move(move[0], move[1]);
+        updateGameInfo(game);
+        
+        // AIが手を打てなかった場合の処理
+        if (!aiMoved && game.get_valid_moves_count() === 0) {
+            checkGameEnd();
+        }
+    }
+    
+    // 強さの自動調整（状態は localStorage に保存し、次回も続きから調整する）
+    const ADAPTIVE_STORAGE_KEY = 'othello-adaptive-strength';
+    let adaptive = loadAdaptiveState();
+    
     // ゲーム開始
     function startNewGame() {
+        cancelAiSearch();
         game = new OthelloGame(canvas);
+        reviewId++;
+        document.getElementById('review-section').hidden = true;
+        document.getElementById('ai-explanation').textContent = '';
         gameInProgress = true;
         
         // AI難易度を設定
@@ -47,14 +175,19 @@ async function run() {
         const difficulty = parseInt(difficultySelect.value);
         game.set_ai_difficulty(difficulty);
         
+        // AIの個性と手のばらつき（ゲームごとに乱数のシードを変える）
+        game.set_ai_personality(parseInt(document.getElementById('ai-personality').value));
+        game.set_ai_variety(document.getElementById('ai-variety').checked, newSeed());
+        applyAdaptiveStrength();
+        
+        applyViewSettings();
         updateGameInfo(game);
         
         // プレイヤーが後攻（白）の場合、AIが最初に手を打つ
         if (playerColor === 2) {
             setTimeout(() => {
                 if (gameInProgress && game.current_player === 1) {
-                    game.make_ai_move();
-                    updateGameInfo(game);
+                    playAiTurn();
                 }
             }, 1000);
         }
@@ -62,15 +195,9 @@ async function run() {
     
     startNewGame();
     
-    // クリックイベントを設定
-    canvas.addEventListener('click', async (event) => {
-        // ゲームが進行中でない、またはAIの番の場合は何もしない
-        if (!gameInProgress || game.current_player !== playerColor) {
-            return;
-        }
-        
-        // プレイヤーの手を処理
-        game.handle_click(event);
+    // プレイヤーが石を置いた後の処理（before は着手前の局面）
+    function afterPlayerMove(before) {
+        recordPlayerMove(before);
         updateGameInfo(game);
         
         // ゲーム終了チェック
@@ -82,16 +209,49 @@ async function run() {
         if (game.current_player !== playerColor) {
             setTimeout(() => {
                 if (gameInProgress && game.current_player !== playerColor) {
-                    const aiMoved = game.make_ai_move();
-                    updateGameInfo(game);
-                    
-                    // AIが手を打てなかった場合の処理
-                    if (!aiMoved && game.get_valid_moves_count() === 0) {
-                        checkGameEnd();
-                    }
+                    playAiTurn();
                 }
             }, 800); // 0.8秒後にAIが手を打つ
         }
+    }
+    
+    // プレイヤーの手番かどうか
+    function isPlayerTurn() {
+        return gameInProgress && game.current_player === playerColor;
+    }
+    
+    // ポインタ（マウス・タッチ・ペン）イベントを設定
+    canvas.addEventListener('pointermove', (event) => {
+        game.handle_pointer(event);
+    });
+    canvas.addEventListener('pointerleave', (event) => {
+        game.handle_pointer(event);
+    });
+    canvas.addEventListener('pointercancel', (event) => {
+        game.handle_pointer(event);
+    });
+    canvas.addEventListener('pointerup', (event) => {
+        // ゲームが進行中でない、またはAIの番の場合は何もしない
+        if (!isPlayerTurn()) {
+            return;
+        }
+        
+        const before = game.export_position();
+        if (game.handle_pointer(event)) {
+            afterPlayerMove(before);
+        }
+    });
+    
+    // キーボード操作（矢印キーでカーソル移動、Enter / Space で着手）
+    canvas.addEventListener('keydown', (event) => {
+        if (!isPlayerTurn()) {
+            return;
+        }
+        
+        const before = game.export_position();
+        if (game.handle_key(event)) {
+            afterPlayerMove(before);
+        }
     });
     
     // リセットボタン
@@ -106,15 +266,234 @@ async function run() {
         startNewGame();
     });
     
+    // 局面図（SVG）を保存
+    document.getElementById('export-svg').addEventListener('click', () => {
+        // 直前の手を強調表示し、石に手数を表示する
+        const history = game.get_move_history();
+        const highlights = history.length >= 3
+            ? [history[history.length - 3], history[history.length - 2]]
+            : [];
+        const svg = game.export_svg(true, new Int32Array(highlights));
+        
+        const blob = new Blob([svg], { type: 'image/svg+xml' });
+        const url = URL.createObjectURL(blob);
+        const link = document.createElement('a');
+        link.href = url;
+        link.download = 'othello.svg';
+        link.click();
+        URL.revokeObjectURL(url);
+    });
+    
+    // 対局の振り返り（各手を AI の最善手と比べ、悪手などを表示する）
+    // 全手の探索と終盤の完全読みを行うため、AI の探索とは別の Web Worker で分析する
+    let reviewWorker = null;
+    let reviewId = 0;
+    
+    function showReview(moveCount, summary, advantages, probabilities) {
+        document.getElementById('review-result').textContent = moveCount > 0 ? summary : 'まだ手が打たれていません';
+        drawEvaluationGraph(advantages, probabilities);
+    }
+    
+    // Worker が使えない環境では、表示を更新してからメインスレッドで分析する
+    function reviewOnMainThread(id) {
+        setTimeout(() => {
+            if (id !== reviewId) {
+                return;
+            }
+            try {
+                const review = game.review();
+                showReview(review.move_count, review.summary(), review.advantages(), review.win_probabilities());
+                review.free();
+            } catch (error) {
+                console.error('振り返りに失敗しました:', error);
+                document.getElementById('review-result').textContent = '振り返りに失敗しました';
+            }
+        }, 0);
+    }
+    
+    document.getElementById('review-game').addEventListener('click', () => {
+        document.getElementById('review-section').hidden = false;
+        document.getElementById('review-result').textContent = '分析中…';
+        const id = ++reviewId;
+        
+        if (!reviewWorker && aiWorker) {
+            try {
+                reviewWorker = new Worker('./worker.js', { type: 'module' });
+                reviewWorker.addEventListener('message', (event) => {
+                    const message = event.data;
+                    // 新しい対局を始めた・もう一度分析した場合は古い結果を無視
+                    if (message.id !== reviewId) {
+                        return;
+                    }
+                    if (message.type === 'review') {
+                        showReview(message.moveCount, message.summary, message.advantages, message.probabilities);
+                    } else {
+                        console.error('振り返りに失敗しました:', message.error);
+                        document.getElementById('review-result').textContent = '振り返りに失敗しました';
+                    }
+                });
+                reviewWorker.addEventListener('error', (event) => {
+                    console.warn('Web Worker でエラーが発生したため、メインスレッドで振り返ります:', event.message);
+                    reviewWorker = null;
+                    reviewOnMainThread(reviewId);
+                });
+            } catch (error) {
+                reviewWorker = null;
+            }
+        }
+        
+        if (reviewWorker) {
+            reviewWorker.postMessage({ type: 'review', id, transcript: game.get_transcript() });
+        } else {
+            reviewOnMainThread(id);
+        }
+    });
+    
+    // 評価値の推移のグラフ（棒: 予想される最終石差、線: 黒の勝率。上が黒優勢）
+    function drawEvaluationGraph(advantages, probabilities) {
+        const graph = document.getElementById('evaluation-graph');
+        const context = graph.getContext('2d');
+        const { width, height } = graph;
+        context.clearRect(0, 0, width, height);
+        
+        const middle = height / 2;
+        const step = width / Math.max(advantages.length, 1);
+        const peak = Math.max(8, ...Array.from(advantages, Math.abs));
+        const scale = Math.ceil(peak / 8) * 8;
+        
+        advantages.forEach((advantage, ply) => {
+            const barHeight = (advantage / scale) * (middle - 4);
+            context.fillStyle = advantage >= 0 ? '#333' : '#ccc';
+            context.fillRect(ply * step, middle - Math.max(barHeight, 0), Math.max(step - 1, 1), Math.abs(barHeight));
+        });
+        
+        context.strokeStyle = '#999';
+        context.beginPath();
+        context.moveTo(0, middle);
+        context.lineTo(width, middle);
+        context.stroke();
+        
+        context.strokeStyle = '#2a7';
+        context.lineWidth = 2;
+        context.beginPath();
+        probabilities.forEach((probability, ply) => {
+            const x = ply * step + step / 2;
+            const y = height - probability * height;
+            if (ply === 0) {
+                context.moveTo(x, y);
+            } else {
+                context.lineTo(x, y);
+            }
+        });
+        context.stroke();
+        context.lineWidth = 1;
+        
+        context.fillStyle = '#666';
+        context.font = '11px sans-serif';
+        context.fillText(`黒 +${scale}`, 4, 12);
+        context.fillText(`白 +${scale}`, 4, height - 4);
+    }
+    
+    // 盤面の表示の向き（回転・左右反転）を反映
+    function applyViewSettings() {
+        const rotation = parseInt(document.getElementById('view-rotation').value);
+        const mirrored = document.getElementById('view-mirrored').checked;
+        const heatmapEnabled = document.getElementById('heatmap-enabled').checked;
+        const stableMarksEnabled = document.getElementById('stable-marks-enabled').checked;
+        game.set_view_rotation(rotation);
+        game.set_view_mirrored(mirrored);
+        game.set_heatmap_enabled(heatmapEnabled);
+        game.set_stable_marks_enabled(stableMarksEnabled);
+    }
+    
+    document.getElementById('view-rotation').addEventListener('change', applyViewSettings);
+    document.getElementById('view-mirrored').addEventListener('change', applyViewSettings);
+    document.getElementById('heatmap-enabled').addEventListener('change', applyViewSettings);
+    document.getElementById('stable-marks-enabled').addEventListener('change', applyViewSettings);
+    
     // AI難易度変更
     document.getElementById('ai-difficulty').addEventListener('change', (event) => {
         const difficulty = parseInt(event.target.value);
         if (game) {
             game.set_ai_difficulty(difficulty);
+            game.draw_board(); // ヒートマップを新しい難易度の評価値で描き直す
             console.log('AI難易度を変更:', game.get_ai_difficulty_description());
         }
     });
     
+    // AIの個性変更
+    document.getElementById('ai-personality').addEventListener('change', (event) => {
+        if (game) {
+            game.set_ai_personality(parseInt(event.target.value));
+            game.draw_board();
+        }
+    });
+    
+    // AIの手のばらつきの切り替え
+    document.getElementById('ai-variety').addEventListener('change', (event) => {
+        if (game) {
+            game.set_ai_variety(event.target.checked, newSeed());
+        }
+    });
+    
+    function loadAdaptiveState() {
+        try {
+            const state = localStorage.getItem(ADAPTIVE_STORAGE_KEY);
+            if (state) {
+                return AdaptiveDifficulty.from_state(state);
+            }
+        } catch (error) {
+            console.warn('強さの自動調整の状態を読み込めませんでした:', error);
+        }
+        return new AdaptiveDifficulty();
+    }
+    
+    function saveAdaptiveState() {
+        try {
+            localStorage.setItem(ADAPTIVE_STORAGE_KEY, adaptive.state);
+        } catch (error) {
+            console.warn('強さの自動調整の状態を保存できませんでした:', error);
+        }
+    }
+    
+    function isAdaptive() {
+        return document.getElementById('ai-adaptive').checked;
+    }
+    
+    // 自動調整が有効なら AI の強さを調整後の値にし、無効なら難易度に戻す
+    function applyAdaptiveStrength() {
+        if (isAdaptive()) {
+            game.set_ai_strength(adaptive.strength);
+        } else {
+            game.clear_ai_strength();
+        }
+    }
+    
+    // プレイヤーの着手の精度を記録し、対局中も AI の強さを調整する
+    function recordPlayerMove(before) {
+        if (!isAdaptive()) {
+            return;
+        }
+        const history = game.get_move_history();
+        const [row, col] = history.slice(-3);
+        adaptive.record_move(before, row, col);
+        saveAdaptiveState();
+        applyAdaptiveStrength();
+    }
+    
+    document.getElementById('ai-adaptive').addEventListener('change', () => {
+        if (game) {
+            applyAdaptiveStrength();
+            game.draw_board();
+            console.log('AIの強さ:', game.get_ai_difficulty_description());
+        }
+    });
+    
+    // 手のばらつき用の乱数のシード
+    function newSeed() {
+        return Math.floor(Math.random() * 0x100000000);
+    }
+    
     // ゲーム終了チェック
     function checkGameEnd() {
         const validMoves = game.get_valid_moves_count();
@@ -148,6 +527,12 @@ async function run() {
             aiScore = blackScore;
         }
         
+        if (isAdaptive()) {
+            adaptive.record_game(playerScore, aiScore);
+            saveAdaptiveState();
+            console.log(`強さの自動調整: 推定レーティング ${adaptive.rating.toFixed(0)} (${adaptive.games} 局)`);
+        }
+        
         if (playerScore > aiScore) {
             result = `あなたの勝利！ (あなた: ${playerScore}, AI: ${aiScore})`;
         } else if (aiScore > playerScore) {
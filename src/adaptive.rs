@@ -0,0 +1,282 @@
+// プレイヤーの強さに合わせて AI の強さを調整するモード
+//
+// プレイヤーのレーティングを Strength と同じ尺度で推定し、AI をそのレーティングの強さにする。
+// 対局の結果（勝ち・負け）で Elo 方式に更新するほか、対局中もプレイヤーの着手を
+// AI の評価と比べた精度がいつもより良いか悪いかで AI の強さを少しずつ上下させる。
+// 状態は文字列にして保存し、次回の起動時に復元できる。
+
+use std::fmt;
+use std::str::FromStr;
+
+use wasm_bindgen::prelude::*;
+
+use crate::ai::{AiConfig, AiDifficulty, Strategy};
+use crate::game::Game;
+use crate::review::eval_to_discs;
+use crate::strength::Strength;
+
+// 着手の精度を判定する探索の深さ
+const JUDGE_DEPTH: u8 = 4;
+
+// 精度 exp(-石数の損失 / LOSS_SCALE) の尺度（石数）。初級（貪欲法）の手の精度の平均が、いつもの精度の初期値 0.5 に
+// なる値（tournament の開始局面 8 個からの初級同士の 414 手で 2.16。examples/loss_scale.rs で当てはめ直せる）
+const LOSS_SCALE: f64 = 2.2;
+
+// 着手の精度がいつもと 1.0 違うときに対局中の補正を動かす量
+const MOVE_K: f64 = 10.0;
+
+// 対局中の補正の上限
+const MAX_IN_GAME: f64 = 150.0;
+
+// 対局の結果による更新の係数（最初の PROVISIONAL_GAMES 局は大きく動かす）
+const GAME_K: f64 = 32.0;
+const PROVISIONAL_K: f64 = 64.0;
+const PROVISIONAL_GAMES: u32 = 10;
+
+// 対局終了時にレーティングへ残す対局中の補正の割合
+const IN_GAME_CARRY: f64 = 0.5;
+
+// いつもの精度（指数移動平均）の更新の割合
+const ACCURACY_RATE: f64 = 0.05;
+
+// 最善手の評価値 best に対する評価値 score の手の損失（石数。終局まで読み切った値も石差に直して比べる）
+fn move_loss(best: i32, score: i32) -> i32 {
+    (eval_to_discs(best) - eval_to_discs(score)).max(0)
+}
+
+// 強さを調整する状態
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveStrength {
+    pub rating: f64,   // プレイヤーの推定レーティング（Strength::elo と同じ尺度）
+    pub games: u32,    // 記録した対局数
+    pub accuracy: f64, // プレイヤーのいつもの着手の精度（0〜1）
+    pub in_game: f64,  // 対局中の着手の精度による補正
+}
+
+impl Default for AdaptiveStrength {
+    // 中級の強さから始める
+    fn default() -> Self {
+        AdaptiveStrength {
+            rating: AiDifficulty::Medium.strength().elo(),
+            games: 0,
+            accuracy: 0.5,
+            in_game: 0.0,
+        }
+    }
+}
+
+impl AdaptiveStrength {
+    pub fn new() -> Self {
+        AdaptiveStrength::default()
+    }
+
+    // 現在の AI の強さ
+    pub fn strength(&self) -> Strength {
+        Strength::from_elo(self.rating + self.in_game)
+    }
+
+    // 現在の AI の設定
+    pub fn config(&self) -> AiConfig {
+        self.strength().config()
+    }
+
+    // プレイヤーが game の局面で (row, col) に打った手を記録し、その手の精度（0〜1）を返す。
+    // 有効な手が1つしかない場合と無効な手の場合は記録せず None を返す
+    pub fn record_move(&mut self, game: &Game, row: usize, col: usize) -> Option<f64> {
+        let evaluations = game.evaluate_moves_with(Strategy::AlphaBeta(JUDGE_DEPTH));
+        if evaluations.len() < 2 {
+            return None;
+        }
+        let best = evaluations.iter().map(|&(_, score)| score).max()?;
+        let (_, score) = evaluations.iter().find(|&&(square, _)| square == (row, col))?;
+
+        let accuracy = (-(move_loss(best, *score) as f64) / LOSS_SCALE).exp();
+        self.record_accuracy(accuracy);
+        Some(accuracy)
+    }
+
+    // 着手の精度を記録（いつもより良ければ AI を強く、悪ければ弱くする）
+    pub fn record_accuracy(&mut self, accuracy: f64) {
+        let accuracy = accuracy.clamp(0.0, 1.0);
+        self.in_game = (self.in_game + MOVE_K * (accuracy - self.accuracy)).clamp(-MAX_IN_GAME, MAX_IN_GAME);
+        self.accuracy += ACCURACY_RATE * (accuracy - self.accuracy);
+    }
+
+    // 対局の結果を記録（プレイヤーの得点: 勝ち 1, 引き分け 0.5, 負け 0）
+    pub fn record_game(&mut self, score: f64) {
+        // AI の強さには上限・下限があるため、実際に対局した強さとの差から期待される得点を求める
+        let opponent = self.strength().elo();
+        let expected = 1.0 / (1.0 + 10f64.powf((opponent - self.rating) / 400.0));
+        let k = if self.games < PROVISIONAL_GAMES { PROVISIONAL_K } else { GAME_K };
+
+        let (lowest, highest) = (Strength::new(0.0).elo(), Strength::new(100.0).elo());
+        self.rating = (self.rating + IN_GAME_CARRY * self.in_game + k * (score.clamp(0.0, 1.0) - expected))
+            .clamp(lowest - 400.0, highest + 400.0);
+        self.in_game = 0.0;
+        self.games += 1;
+    }
+
+    // 終局した対局の結果を記録（player はプレイヤーの色。1: 黒, 2: 白）
+    pub fn record_result(&mut self, game: &Game, player: i8) {
+        let (black, white) = game.score();
+        let diff = if player == 1 { black - white } else { white - black };
+        self.record_game(match diff.signum() {
+            1 => 1.0,
+            0 => 0.5,
+            _ => 0.0,
+        });
+    }
+}
+
+impl fmt::Display for AdaptiveStrength {
+    // "rating=564.0,games=3,accuracy=0.612,in_game=0.0" の形式（FromStr で元に戻せる）
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rating={:.1},games={},accuracy={:.4},in_game={:.1}", self.rating, self.games, self.accuracy, self.in_game)
+    }
+}
+
+impl FromStr for AdaptiveStrength {
+    type Err = String;
+
+    // Display の形式を解釈する（指定しなかった項目は既定値）
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut state = AdaptiveStrength::default();
+        for item in text.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+            let (name, value) = item.split_once('=').ok_or_else(|| format!("不正な指定です: {}", item))?;
+            let number = || value.trim().parse::<f64>().ok().filter(|value| value.is_finite()).ok_or_else(|| format!("不正な値です: {}", item));
+            match name.trim() {
+                "rating" => state.rating = number()?,
+                "games" => state.games = value.trim().parse().map_err(|_| format!("不正な値です: {}", item))?,
+                "accuracy" => state.accuracy = number()?.clamp(0.0, 1.0),
+                "in_game" => state.in_game = number()?.clamp(-MAX_IN_GAME, MAX_IN_GAME),
+                other => return Err(format!("不明な項目です: {}", other)),
+            }
+        }
+        Ok(state)
+    }
+}
+
+// JavaScript から使う強さの調整（状態は state で文字列にして localStorage などに保存する）
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct AdaptiveDifficulty {
+    inner: AdaptiveStrength,
+}
+
+#[wasm_bindgen]
+impl AdaptiveDifficulty {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> AdaptiveDifficulty {
+        AdaptiveDifficulty::default()
+    }
+
+    // 保存しておいた状態の文字列から復元
+    pub fn from_state(state: &str) -> Result<AdaptiveDifficulty, JsValue> {
+        let inner = state.parse().map_err(|e: String| JsValue::from_str(&e))?;
+        Ok(AdaptiveDifficulty { inner })
+    }
+
+    // 状態を文字列で取得
+    #[wasm_bindgen(getter)]
+    pub fn state(&self) -> String {
+        self.inner.to_string()
+    }
+
+    // 現在の AI の強さ（0〜100。OthelloGame.set_ai_strength に渡す）
+    #[wasm_bindgen(getter)]
+    pub fn strength(&self) -> f64 {
+        self.inner.strength().value()
+    }
+
+    // プレイヤーの推定レーティング
+    #[wasm_bindgen(getter)]
+    pub fn rating(&self) -> f64 {
+        self.inner.rating
+    }
+
+    // 記録した対局数
+    #[wasm_bindgen(getter)]
+    pub fn games(&self) -> u32 {
+        self.inner.games
+    }
+
+    // プレイヤーが局面文字列 position で (row, col) に打った手を記録し、精度（0〜1）を返す（記録しなかった場合は -1）
+    pub fn record_move(&mut self, position: &str, row: usize, col: usize) -> Result<f64, JsValue> {
+        let game = Game::from_position(position).map_err(|e| JsValue::from_str(&e))?;
+        Ok(self.inner.record_move(&game, row, col).unwrap_or(-1.0))
+    }
+
+    // 対局の結果を記録
+    pub fn record_game(&mut self, player_discs: i32, ai_discs: i32) {
+        self.inner.record_game(match player_discs.cmp(&ai_discs) {
+            std::cmp::Ordering::Greater => 1.0,
+            std::cmp::Ordering::Equal => 0.5,
+            std::cmp::Ordering::Less => 0.0,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_round_trips() {
+        let state = AdaptiveStrength {
+            rating: 812.5,
+            games: 12,
+            accuracy: 0.6125,
+            in_game: -37.5,
+        };
+        assert_eq!(state.to_string(), "rating=812.5,games=12,accuracy=0.6125,in_game=-37.5");
+        assert_eq!(state.to_string().parse::<AdaptiveStrength>(), Ok(state));
+        assert_eq!("".parse::<AdaptiveStrength>(), Ok(AdaptiveStrength::default()));
+    }
+
+    #[test]
+    fn invalid_state_is_rejected() {
+        for text in ["level=3", "rating", "rating=NaN", "rating=inf", "accuracy=-inf", "in_game=nan", "games=-1", "games=1.5"] {
+            assert!(text.parse::<AdaptiveStrength>().is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn game_results_move_the_rating() {
+        // AI がプレイヤーと同じレーティングなら期待される得点は 0.5
+        let start = AdaptiveStrength::default();
+        for (games, k) in [(0, PROVISIONAL_K), (PROVISIONAL_GAMES - 1, PROVISIONAL_K), (PROVISIONAL_GAMES, GAME_K)] {
+            let mut won = AdaptiveStrength { games, ..start };
+            won.record_game(1.0);
+            assert!((won.rating - (start.rating + k * 0.5)).abs() < 1e-6, "{} 局目", games);
+            assert_eq!(won.games, games + 1);
+
+            let mut lost = AdaptiveStrength { games, ..start };
+            lost.record_game(0.0);
+            assert!((lost.rating - (start.rating - k * 0.5)).abs() < 1e-6, "{} 局目", games);
+        }
+
+        // 対局中の補正は半分だけレーティングに残り、0 に戻る
+        let mut drawn = AdaptiveStrength { in_game: 40.0, games: PROVISIONAL_GAMES, ..start };
+        let expected = 1.0 / (1.0 + 10f64.powf((drawn.strength().elo() - drawn.rating) / 400.0));
+        drawn.record_game(0.5);
+        assert!((drawn.rating - (start.rating + IN_GAME_CARRY * 40.0 + GAME_K * (0.5 - expected))).abs() < 1e-6);
+        assert_eq!(drawn.in_game, 0.0);
+    }
+
+    #[test]
+    fn move_accuracy_nudges_the_ai() {
+        let mut state = AdaptiveStrength::default();
+        state.record_accuracy(1.0);
+        assert!(state.in_game > 0.0 && state.accuracy > 0.5);
+        let mut state = AdaptiveStrength::default();
+        state.record_accuracy(0.0);
+        assert!(state.in_game < 0.0 && state.accuracy < 0.5);
+
+        let mut state = AdaptiveStrength { in_game: MAX_IN_GAME - 1.0, ..AdaptiveStrength::default() };
+        state.record_accuracy(1.0);
+        assert_eq!(state.in_game, MAX_IN_GAME);
+        let mut state = AdaptiveStrength { in_game: -MAX_IN_GAME + 1.0, ..AdaptiveStrength::default() };
+        state.record_accuracy(0.0);
+        assert_eq!(state.in_game, -MAX_IN_GAME);
+    }
+}
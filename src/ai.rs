@@ -0,0 +1,1034 @@
+// AIの探索と評価関数
+//
+// 盤面（BoardState）と手番だけを受け取るため、OthelloGame の外
+// （Web Worker など）からも同じ探索を実行できる。
+
+use std::cell::Cell;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use wasm_bindgen::prelude::*;
+
+use crate::bitboard::{adjacent, Bitboard};
+use crate::board::BoardState;
+use crate::book;
+use crate::rng::Rng;
+use crate::strength::Strength;
+use crate::tt::{Bound, TableEntry, TranspositionTable};
+
+// 置換表の大きさ（2^TABLE_BITS 項目）
+const TABLE_BITS: u32 = 16;
+
+// 探索の窓の端（符号を反転してもあふれないよう i32::MIN は使わない）
+pub(crate) const INFINITY: i32 = i32::MAX;
+
+// 終局した局面の評価値の基準（勝敗を評価関数のどの値よりも優先し、同じ勝敗なら石差で比べる）
+pub(crate) const WIN_SCORE: i32 = 1_000_000;
+
+// 確定石・フロンティア・潜在的な着手可能数・パリティ・X マス・C マスの重み（深さ 3 の自己対戦で、0 倍や 2 倍に変えても強くならなかった値）
+const STABILITY_WEIGHT: i32 = 40;
+const FRONTIER_WEIGHT: i32 = 15;
+const POTENTIAL_MOBILITY_WEIGHT: i32 = 5;
+const PARITY_WEIGHT: i32 = 20;
+const X_SQUARE_WEIGHT: i32 = 30;
+const C_SQUARE_WEIGHT: i32 = 10;
+
+// 角と、その斜め隣（X マス）・辺の隣（C マス）
+type Square = (usize, usize);
+const CORNER_SQUARES: [(Square, Square, [Square; 2]); 4] = [
+    ((0, 0), (1, 1), [(0, 1), (1, 0)]),
+    ((0, 7), (1, 6), [(0, 6), (1, 7)]),
+    ((7, 0), (6, 1), [(7, 1), (6, 0)]),
+    ((7, 7), (6, 6), [(7, 6), (6, 7)]),
+];
+
+// AI難易度を表現する列挙型
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub enum AiDifficulty {
+    Easy = 1,    // 貪欲法
+    Medium = 2,  // ミニマックス 3手先読み
+    Hard = 3,    // ミニマックス + アルファベータ 5手先読み
+    Expert = 4,  // ミニマックス + アルファベータ 7手先読み + 改良評価関数
+}
+
+impl FromStr for AiDifficulty {
+    type Err = String;
+
+    // 難易度名（easy / medium / hard / expert または 1〜4）を解釈
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "easy" | "1" => Ok(AiDifficulty::Easy),
+            "medium" | "2" => Ok(AiDifficulty::Medium),
+            "hard" | "3" => Ok(AiDifficulty::Hard),
+            "expert" | "4" => Ok(AiDifficulty::Expert),
+            _ => Err(format!("不明な難易度です: {}", text)),
+        }
+    }
+}
+
+// 難易度ごとの探索方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    Greedy,        // 貪欲法
+    Minimax(u8),   // ミニマックス法（探索の深さ）
+    AlphaBeta(u8), // アルファベータ法（探索の深さ）
+}
+
+impl AiDifficulty {
+    // 難易度の説明
+    pub fn description(self) -> &'static str {
+        match self {
+            AiDifficulty::Easy => "初級 (貪欲法)",
+            AiDifficulty::Medium => "中級 (ミニマックス 3手先読み)",
+            AiDifficulty::Hard => "上級 (アルファベータ 5手先読み)",
+            AiDifficulty::Expert => "エキスパート (アルファベータ 7手先読み + 高度評価)",
+        }
+    }
+
+    // 難易度に対応する連続的な強さ（この強さの設定は難易度の探索方法と同じ手を選ぶ）
+    pub fn strength(self) -> Strength {
+        Strength::from(self)
+    }
+
+    // 難易度に対応する探索方法
+    pub fn strategy(self) -> Strategy {
+        match self {
+            AiDifficulty::Easy => Strategy::Greedy,
+            AiDifficulty::Medium => Strategy::Minimax(3),
+            AiDifficulty::Hard => Strategy::AlphaBeta(5),
+            AiDifficulty::Expert => Strategy::AlphaBeta(7),
+        }
+    }
+}
+
+// 評価関数の重み（項目ごとに指定できる）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvalWeights {
+    pub position: i32, // マスの位置による重み（序盤はさらに2倍）
+    pub mobility: i32, // 中盤の着手可能数の差
+    pub discs: i32,    // 終盤の石数の差
+    pub corners: i32,  // 角の石
+    pub edges: i32,    // 辺の石
+    pub stability: i32, // 確定石の差
+    pub material: i32, // 序盤から終盤まで常に加える石数の差（負の値にすると石を取りすぎない打ち方になる）
+    pub frontier: i32, // 中盤の空きマスに接する石（フロンティア）の差（少ない方が良い）
+    pub potential_mobility: i32, // 中盤の相手の石に接する空きマス（潜在的な着手可能数）の差
+    pub parity: i32,   // 終盤に最後の一手を打てるか（偶数理論）
+    pub x_squares: i32, // 空いている角の斜め隣（X マス）の石（少ない方が良い）
+    pub c_squares: i32, // 空いている角の隣の辺（C マス）の石（少ない方が良い）
+}
+
+impl Default for EvalWeights {
+    fn default() -> Self {
+        EvalWeights {
+            position: 1,
+            mobility: 10,
+            discs: 10,
+            corners: 25,
+            edges: 5,
+            stability: STABILITY_WEIGHT,
+            material: 0,
+            frontier: FRONTIER_WEIGHT,
+            potential_mobility: POTENTIAL_MOBILITY_WEIGHT,
+            parity: PARITY_WEIGHT,
+            x_squares: X_SQUARE_WEIGHT,
+            c_squares: C_SQUARE_WEIGHT,
+        }
+    }
+}
+
+impl EvalWeights {
+    // 名前を指定して重みを1つ設定
+    pub fn set(&mut self, name: &str, value: i32) -> Result<(), String> {
+        let weight = match name {
+            "position" => &mut self.position,
+            "mobility" => &mut self.mobility,
+            "discs" => &mut self.discs,
+            "corners" => &mut self.corners,
+            "edges" => &mut self.edges,
+            "stability" => &mut self.stability,
+            "material" => &mut self.material,
+            "frontier" => &mut self.frontier,
+            "potential_mobility" => &mut self.potential_mobility,
+            "parity" => &mut self.parity,
+            "x_squares" => &mut self.x_squares,
+            "c_squares" => &mut self.c_squares,
+            _ => return Err(format!("不明な評価項目です: {}", name)),
+        };
+        *weight = value;
+        Ok(())
+    }
+
+    // 項目名と重みの一覧（EvalBreakdown::terms と同じ名前・同じ順）
+    pub fn terms(&self) -> [(&'static str, i32); 12] {
+        EvalBreakdown {
+            position: self.position,
+            mobility: self.mobility,
+            discs: self.discs,
+            corners: self.corners,
+            edges: self.edges,
+            stability: self.stability,
+            material: self.material,
+            frontier: self.frontier,
+            potential_mobility: self.potential_mobility,
+            parity: self.parity,
+            x_squares: self.x_squares,
+            c_squares: self.c_squares,
+        }
+        .terms()
+    }
+}
+
+impl FromStr for EvalWeights {
+    type Err = String;
+
+    // "mobility=20,corners=40" の形式（指定しなかった項目は既定値）
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut weights = EvalWeights::default();
+        for item in text.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+            let (name, value) = item.split_once('=').ok_or_else(|| format!("不正な指定です: {}", item))?;
+            let value = value.trim().parse().map_err(|_| format!("不正な値です: {}", item))?;
+            weights.set(name.trim(), value)?;
+        }
+        Ok(weights)
+    }
+}
+
+// 評価値の項目ごとの内訳（評価したプレイヤーの視点。重みを掛けた後の値）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EvalBreakdown {
+    pub position: i32,
+    pub mobility: i32,
+    pub discs: i32,
+    pub corners: i32,
+    pub edges: i32,
+    pub stability: i32,
+    pub material: i32,
+    pub frontier: i32,
+    pub potential_mobility: i32,
+    pub parity: i32,
+    pub x_squares: i32,
+    pub c_squares: i32,
+}
+
+impl EvalBreakdown {
+    // 項目名と値の一覧（名前は EvalWeights の指定と同じ）
+    pub fn terms(&self) -> [(&'static str, i32); 12] {
+        [
+            ("position", self.position),
+            ("mobility", self.mobility),
+            ("discs", self.discs),
+            ("corners", self.corners),
+            ("edges", self.edges),
+            ("stability", self.stability),
+            ("material", self.material),
+            ("frontier", self.frontier),
+            ("potential_mobility", self.potential_mobility),
+            ("parity", self.parity),
+            ("x_squares", self.x_squares),
+            ("c_squares", self.c_squares),
+        ]
+    }
+
+    // 評価値（全項目の合計）
+    pub fn total(&self) -> i32 {
+        self.terms().iter().map(|&(_, value)| value).sum()
+    }
+}
+
+impl fmt::Display for EvalBreakdown {
+    // 1行に1項目、最後に合計
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value) in self.terms() {
+            writeln!(f, "{:<20}{:+6}", name, value)?;
+        }
+        write!(f, "{:<20}{:+6}", "total", self.total())
+    }
+}
+
+// AIの個性（評価関数の重みの組み合わせ）
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Personality {
+    Balanced = 0,       // 標準の評価関数
+    Aggressive = 1,     // 石を多く取りにいく
+    CornerHungry = 2,   // 角と辺を最優先する
+    MobilityMinded = 3, // 着手可能数を重視し、石を取りすぎない
+}
+
+impl Personality {
+    // 個性の説明
+    pub fn description(self) -> &'static str {
+        match self {
+            Personality::Balanced => "標準",
+            Personality::Aggressive => "攻撃的 (石を多く取る)",
+            Personality::CornerHungry => "角好き (角と辺を優先)",
+            Personality::MobilityMinded => "機動力重視 (着手可能数を優先)",
+        }
+    }
+
+    // 個性に対応する評価関数の重み
+    pub fn weights(self) -> EvalWeights {
+        let base = EvalWeights::default();
+        match self {
+            Personality::Balanced => base,
+            Personality::Aggressive => EvalWeights { mobility: 5, material: 15, ..base },
+            Personality::CornerHungry => EvalWeights { corners: 80, edges: 10, ..base },
+            Personality::MobilityMinded => EvalWeights { mobility: 25, material: -3, ..base },
+        }
+    }
+}
+
+impl FromStr for Personality {
+    type Err = String;
+
+    // 個性の名前（balanced / aggressive / corner-hungry / mobility-minded または 0〜3）を解釈
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "balanced" | "0" => Ok(Personality::Balanced),
+            "aggressive" | "1" => Ok(Personality::Aggressive),
+            "corner-hungry" | "corners" | "2" => Ok(Personality::CornerHungry),
+            "mobility-minded" | "mobility" | "3" => Ok(Personality::MobilityMinded),
+            _ => Err(format!("不明な個性です: {}", text)),
+        }
+    }
+}
+
+// variety を指定したときのソフトマックスの温度と候補にする評価値の差
+const VARIETY_TEMPERATURE: f64 = 8.0;
+const VARIETY_MARGIN: i32 = 24;
+
+// AIの設定（探索方法・評価関数の重み・1手あたりの持ち時間・手のばらつき）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AiConfig {
+    pub strategy: Strategy,
+    pub weights: EvalWeights,
+    // 持ち時間（ネイティブ環境のみ）。指定した場合は strategy の深さを上限に、時間内に読み切れた最も深い結果を使う
+    pub time_limit: Option<Duration>,
+    pub noise: i32,   // 手を選ぶ前に各手の評価値に加える乱数の幅（±noise）
+    pub blunder: f64, // 評価を無視して有効な手から無作為に選ぶ確率
+    pub seed: u64,    // 乱数のシード（局面ごとに混ぜるため、同じ局面・同じシードでは同じ手になる）
+    // 最善手との評価値の差が margin 以内の手から exp(差 / temperature) に比例する確率で選ぶ（0 なら常に最善手）
+    pub temperature: f64,
+    pub margin: i32,
+    pub book: bool, // 定石の局面では定石の候補手から無作為に選ぶ
+}
+
+impl AiConfig {
+    // 毎局同じ手順にならないよう、定石と最善手に近い手から seed に従って選ぶ設定にする
+    pub fn with_variety(self, seed: u64) -> Self {
+        AiConfig {
+            temperature: VARIETY_TEMPERATURE,
+            margin: VARIETY_MARGIN,
+            book: true,
+            seed,
+            ..self
+        }
+    }
+
+    // 手の選び方が乱数によって変わる設定かどうか
+    fn is_randomized(&self) -> bool {
+        self.noise > 0 || self.blunder > 0.0 || self.temperature > 0.0 || self.book
+    }
+}
+
+impl Default for AiConfig {
+    fn default() -> Self {
+        AiDifficulty::Expert.into()
+    }
+}
+
+impl From<Strategy> for AiConfig {
+    fn from(strategy: Strategy) -> Self {
+        AiConfig {
+            strategy,
+            weights: EvalWeights::default(),
+            time_limit: None,
+            noise: 0,
+            blunder: 0.0,
+            seed: 0,
+            temperature: 0.0,
+            margin: 0,
+            book: false,
+        }
+    }
+}
+
+impl From<AiDifficulty> for AiConfig {
+    fn from(difficulty: AiDifficulty) -> Self {
+        difficulty.strategy().into()
+    }
+}
+
+impl FromStr for AiConfig {
+    type Err = String;
+
+    // "hard", "depth=6,time=200,mobility=20" のようなカンマ区切りの指定を解釈する
+    // （難易度名, greedy, depth=深さ, minimax=深さ, time=ミリ秒, strength=0〜100, elo=目標レーティング,
+    //   noise=幅, blunder=確率, seed=シード, temperature=温度, margin=評価値の差, book, variety,
+    //   personality=個性, 評価関数の重み=値）。strength と elo は探索方法・noise・blunder をまとめて設定し、
+    //   personality は評価関数の重みをまとめて設定する（後に書いた重みで個別に上書きできる）
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut config = AiConfig::default();
+        for item in text.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+            let Some((name, value)) = item.split_once('=') else {
+                match item {
+                    "greedy" => config.strategy = Strategy::Greedy,
+                    "book" => config.book = true,
+                    "variety" => config = config.with_variety(config.seed),
+                    _ => config.strategy = item.parse::<AiDifficulty>()?.strategy(),
+                }
+                continue;
+            };
+
+            let (name, value) = (name.trim(), value.trim());
+            let number = || value.parse::<u32>().map_err(|_| format!("不正な値です: {}", item));
+            match name {
+                "difficulty" => config.strategy = value.parse::<AiDifficulty>()?.strategy(),
+                "depth" => config.strategy = Strategy::AlphaBeta(number()?.clamp(1, 60) as u8),
+                "minimax" => config.strategy = Strategy::Minimax(number()?.clamp(1, 60) as u8),
+                "time" => config.time_limit = Some(Duration::from_millis(number()? as u64)),
+                "strength" | "elo" => {
+                    let value = value.parse::<f64>().map_err(|_| format!("不正な値です: {}", item))?;
+                    let strength = if name == "elo" { Strength::from_elo(value) } else { Strength::new(value) };
+                    let AiConfig { strategy, noise, blunder, .. } = strength.config();
+                    config = AiConfig { strategy, noise, blunder, ..config };
+                }
+                "noise" => config.noise = number()? as i32,
+                "blunder" => config.blunder = value.parse::<f64>().map_err(|_| format!("不正な値です: {}", item))?.clamp(0.0, 1.0),
+                "seed" => config.seed = value.parse().map_err(|_| format!("不正な値です: {}", item))?,
+                "temperature" => config.temperature = value.parse::<f64>().map_err(|_| format!("不正な値です: {}", item))?.max(0.0),
+                "margin" => config.margin = number()? as i32,
+                "book" => config.book = matches!(value, "on" | "true" | "1"),
+                "personality" => config.weights = value.parse::<Personality>()?.weights(),
+                _ => config.weights.set(name, value.parse().map_err(|_| format!("不正な値です: {}", item))?)?,
+            }
+        }
+        Ok(config)
+    }
+}
+
+impl fmt::Display for AiConfig {
+    // FromStr で元の設定に戻せる形式（既定値と同じ項目は省略する）
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut items = vec![match self.strategy {
+            Strategy::Greedy => "greedy".to_string(),
+            Strategy::Minimax(depth) => format!("minimax={}", depth),
+            Strategy::AlphaBeta(depth) => format!("depth={}", depth),
+        }];
+
+        for ((name, value), (_, default)) in self.weights.terms().into_iter().zip(EvalWeights::default().terms()) {
+            if value != default {
+                items.push(format!("{}={}", name, value));
+            }
+        }
+
+        if let Some(time_limit) = self.time_limit {
+            items.push(format!("time={}", time_limit.as_millis()));
+        }
+        if self.noise != 0 {
+            items.push(format!("noise={}", self.noise));
+        }
+        if self.blunder != 0.0 {
+            items.push(format!("blunder={}", self.blunder));
+        }
+        if self.temperature != 0.0 {
+            items.push(format!("temperature={}", self.temperature));
+        }
+        if self.margin != 0 {
+            items.push(format!("margin={}", self.margin));
+        }
+        if self.book {
+            items.push("book".to_string());
+        }
+        if self.seed != 0 {
+            items.push(format!("seed={}", self.seed));
+        }
+        write!(f, "{}", items.join(","))
+    }
+}
+
+// 設定に従って各有効手を評価
+pub(crate) fn evaluate_moves(board: &BoardState, player: i8, config: &AiConfig) -> Vec<((usize, usize), i32)> {
+    let moves = board.get_valid_moves_for_board(player);
+
+    // wasm32-unknown-unknown では Instant::now() が使えないため、持ち時間はネイティブ環境でのみ使う
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(time_limit) = config.time_limit {
+        return evaluate_moves_timed(board, player, config, &moves, time_limit);
+    }
+
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    if config.strategy != Strategy::Greedy {
+        return evaluate_moves_parallel(board, player, config, &moves);
+    }
+
+    let search = Search::with_options(player, Arc::new(TranspositionTable::new(TABLE_BITS)), config.weights, None);
+    moves
+        .into_iter()
+        .map(|(row, col)| ((row, col), search.evaluate_move(board, config.strategy, row, col)))
+        .collect()
+}
+
+// 持ち時間内で反復深化し、最後まで探索できた最も深い結果を返す（深さ1は必ず最後まで探索する）
+#[cfg(not(target_arch = "wasm32"))]
+fn evaluate_moves_timed(board: &BoardState, player: i8, config: &AiConfig, moves: &[(usize, usize)], time_limit: Duration) -> Vec<((usize, usize), i32)> {
+    let deadline = Instant::now() + time_limit;
+    let table = Arc::new(TranspositionTable::new(TABLE_BITS));
+    let empties = board.board.iter().flatten().filter(|&&piece| piece == 0).count();
+    let max_depth = match config.strategy {
+        Strategy::Greedy => 1,
+        Strategy::Minimax(depth) | Strategy::AlphaBeta(depth) => depth.max(1),
+    };
+
+    let mut evaluations = Vec::new();
+    for depth in 1..=max_depth {
+        let strategy = match config.strategy {
+            Strategy::Greedy => Strategy::Greedy,
+            Strategy::Minimax(_) => Strategy::Minimax(depth),
+            Strategy::AlphaBeta(_) => Strategy::AlphaBeta(depth),
+        };
+        let search = Search::with_options(player, Arc::clone(&table), config.weights, (depth > 1).then_some(deadline));
+        let results: Vec<_> = moves
+            .iter()
+            .map(|&(row, col)| ((row, col), search.evaluate_move(board, strategy, row, col)))
+            .collect();
+        if search.aborted.get() {
+            break;
+        }
+
+        evaluations = results;
+        if depth as usize >= empties {
+            break; // 終局まで読み切った
+        }
+    }
+    evaluations
+}
+
+// ルートの手を複数スレッドで分担して評価（置換表は全スレッドで共有する）
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+fn evaluate_moves_parallel(board: &BoardState, player: i8, config: &AiConfig, moves: &[(usize, usize)]) -> Vec<((usize, usize), i32)> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    let table = Arc::new(TranspositionTable::new(TABLE_BITS + 4));
+    let threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1).min(moves.len()).max(1);
+    let next = AtomicUsize::new(0);
+
+    let mut scores = vec![0; moves.len()];
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                let table = Arc::clone(&table);
+                let next = &next;
+                scope.spawn(move || {
+                    let search = Search::with_options(player, table, config.weights, None);
+                    let mut results = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(&(row, col)) = moves.get(index) else {
+                            break;
+                        };
+                        results.push((index, search.evaluate_move(board, config.strategy, row, col)));
+                    }
+                    results
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            for (index, score) in worker.join().expect("探索スレッドが異常終了しました") {
+                scores[index] = score;
+            }
+        }
+    });
+
+    moves.iter().copied().zip(scores).collect()
+}
+
+// 設定の book, blunder, noise, temperature に従って評価済みの手から1手を選ぶ（いずれも指定しなければ select_best_move と同じ）
+pub(crate) fn choose_move(board: &BoardState, player: i8, evaluations: &[((usize, usize), i32)], config: &AiConfig) -> Option<(usize, usize)> {
+    if !config.is_randomized() {
+        return select_best_move(evaluations);
+    }
+
+    let mut rng = Rng::new(config.seed ^ board.hash(player));
+    if config.book {
+        let candidates: Vec<_> = book::book_moves(board, player)
+            .iter()
+            .filter(|&&square| evaluations.iter().any(|&(evaluated, _)| evaluated == square))
+            .copied()
+            .collect();
+        if !candidates.is_empty() {
+            return Some(candidates[rng.below(candidates.len())]);
+        }
+    }
+    if !evaluations.is_empty() && rng.next_f64() < config.blunder {
+        return Some(evaluations[rng.below(evaluations.len())].0);
+    }
+    let noisy: Vec<_> = evaluations
+        .iter()
+        .map(|&(square, score)| (square, score.saturating_add(rng.spread(config.noise))))
+        .collect();
+    if config.temperature > 0.0 {
+        return sample_near_best(&noisy, config.temperature, config.margin, &mut rng);
+    }
+    select_best_move(&noisy)
+}
+
+// 最善手との評価値の差が margin 以内の手から、exp(差 / temperature) に比例する確率で1手を選ぶ
+fn sample_near_best(evaluations: &[((usize, usize), i32)], temperature: f64, margin: i32, rng: &mut Rng) -> Option<(usize, usize)> {
+    let best = evaluations.iter().map(|&(_, score)| score).max()?;
+    let candidates: Vec<_> = evaluations
+        .iter()
+        .filter(|&&(_, score)| score >= best.saturating_sub(margin.max(0)))
+        .map(|&(square, score)| (square, ((score as f64 - best as f64) / temperature).exp()))
+        .collect();
+
+    let mut target = rng.next_f64() * candidates.iter().map(|&(_, weight)| weight).sum::<f64>();
+    for &(square, weight) in &candidates {
+        if target < weight {
+            return Some(square);
+        }
+        target -= weight;
+    }
+    candidates.last().map(|&(square, _)| square)
+}
+
+// 評価値が最も高い手を選ぶ（同点の場合は先に見つかった手を優先）
+pub(crate) fn select_best_move(evaluations: &[((usize, usize), i32)]) -> Option<(usize, usize)> {
+    let mut best = *evaluations.first()?;
+    for &evaluation in evaluations {
+        if evaluation.1 > best.1 {
+            best = evaluation;
+        }
+    }
+    Some(best.0)
+}
+
+// 基本的な手の評価スコア（貪欲法用）
+pub(crate) fn calculate_basic_move_score(board: &BoardState, row: usize, col: usize, player: i8) -> i32 {
+    let mut score = 0;
+
+    // 位置による重み付け（戦略的）
+    let position_weights = [
+        [100, -20,  10,   5,   5,  10, -20, 100],
+        [-20, -50,  -2,  -2,  -2,  -2, -50, -20],
+        [ 10,  -2,  -1,  -1,  -1,  -1,  -2,  10],
+        [  5,  -2,  -1,  -1,  -1,  -1,  -2,   5],
+        [  5,  -2,  -1,  -1,  -1,  -1,  -2,   5],
+        [ 10,  -2,  -1,  -1,  -1,  -1,  -2,  10],
+        [-20, -50,  -2,  -2,  -2,  -2, -50, -20],
+        [100, -20,  10,   5,   5,  10, -20, 100],
+    ];
+
+    score += position_weights[row][col];
+
+    // ひっくり返せる石の数を計算（少し重み付け）
+    let directions = [
+        (-1, -1), (-1, 0), (-1, 1),
+        (0, -1),           (0, 1),
+        (1, -1),  (1, 0),  (1, 1),
+    ];
+
+    for (dr, dc) in directions.iter() {
+        score += board.count_flips_in_direction(row, col, *dr, *dc, player) * 2;
+    }
+
+    score
+}
+
+// 終局した局面の player の視点の評価値
+pub(crate) fn final_score(board: &BoardState, player: i8) -> i32 {
+    let diff: i32 = board.board.iter().flatten().map(|&piece| match piece {
+        0 => 0,
+        piece if piece == player => 1,
+        _ => -1,
+    }).sum();
+    diff.signum() * WIN_SCORE + diff
+}
+
+// 探索（内部の評価値は各ノードの手番のプレイヤーの視点、ルートの手の評価値はルートの手番のプレイヤーの視点）
+pub(crate) struct Search {
+    player: i8,
+    table: Arc<TranspositionTable>,
+    weights: EvalWeights,
+    deadline: Option<Instant>, // 探索を打ち切る時刻
+    nodes: Cell<u64>,
+    aborted: Cell<bool>, // 時間切れで探索を打ち切った（評価値は使えない）
+}
+
+impl Search {
+    // 評価関数の重みを指定して探索を作成
+    pub(crate) fn with_weights(player: i8, weights: EvalWeights) -> Self {
+        Search::with_options(player, Arc::new(TranspositionTable::new(TABLE_BITS)), weights, None)
+    }
+
+    // 置換表（並列探索ではスレッド間で共有する）・評価関数の重み・打ち切り時刻を指定して探索を作成
+    pub(crate) fn with_options(player: i8, table: Arc<TranspositionTable>, weights: EvalWeights, deadline: Option<Instant>) -> Self {
+        Search {
+            player,
+            table,
+            weights,
+            deadline,
+            nodes: Cell::new(0),
+            aborted: Cell::new(false),
+        }
+    }
+
+    // 打ち切り時刻を過ぎたか（時刻の確認は一定ノードごとに行う）
+    fn out_of_time(&self) -> bool {
+        let Some(deadline) = self.deadline else {
+            return false;
+        };
+        let nodes = self.nodes.get() + 1;
+        self.nodes.set(nodes);
+        if nodes.is_multiple_of(1024) && Instant::now() >= deadline {
+            self.aborted.set(true);
+        }
+        self.aborted.get()
+    }
+
+    // 指定した探索方法で1つの手を評価
+    pub(crate) fn evaluate_move(&self, board: &BoardState, strategy: Strategy, row: usize, col: usize) -> i32 {
+        match strategy {
+            Strategy::Greedy => calculate_basic_move_score(board, row, col, self.player),
+            Strategy::Minimax(depth) => self.evaluate_minimax_move(board, depth, row, col),
+            Strategy::AlphaBeta(depth) => self.evaluate_alpha_beta_move(board, depth, row, col),
+        }
+    }
+
+    // 置換表から同じ深さの結果を探し、窓を狭める。値が確定した場合はその値を返す
+    pub(crate) fn probe_table(&self, hash: u64, depth: u8, alpha: &mut i32, beta: &mut i32) -> Option<i32> {
+        // 深さの異なる結果は使わない（並列探索でも逐次探索と同じ評価値にするため）
+        let entry = self.table.probe(hash).filter(|entry| entry.depth == depth)?;
+        match entry.bound {
+            Bound::Exact => return Some(entry.value),
+            Bound::Lower => *alpha = (*alpha).max(entry.value),
+            Bound::Upper => *beta = (*beta).min(entry.value),
+        }
+        if *alpha >= *beta {
+            Some(entry.value)
+        } else {
+            None
+        }
+    }
+
+    // 探索結果を置換表に格納（alpha, beta はそのノードを探索したときの窓）
+    pub(crate) fn store_table(&self, hash: u64, depth: u8, alpha: i32, beta: i32, value: i32) {
+        let bound = if value <= alpha {
+            Bound::Upper
+        } else if value >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.store(hash, TableEntry { depth, value, bound });
+    }
+
+    // ミニマックス法で1つの手を評価（相手の視点の評価値を符号反転してルートの視点にする）
+    fn evaluate_minimax_move(&self, board: &BoardState, depth: u8, row: usize, col: usize) -> i32 {
+        let mut game_copy = board.clone();
+        game_copy.make_move_on_board(row, col, self.player);
+        -self.negamax(&game_copy, depth.saturating_sub(1), 3 - self.player)
+    }
+
+    // アルファベータ法で1つの手を評価（各手を全幅の窓で探索するため、評価値は正確な値になる）
+    fn evaluate_alpha_beta_move(&self, board: &BoardState, depth: u8, row: usize, col: usize) -> i32 {
+        let mut game_copy = board.clone();
+        game_copy.make_move_on_board(row, col, self.player);
+        -self.alpha_beta(&game_copy, depth.saturating_sub(1), -INFINITY, INFINITY, 3 - self.player)
+    }
+
+    // ネガマックス法の実装（評価値は常に手番のプレイヤー player の視点）
+    fn negamax(&self, board: &BoardState, depth: u8, player: i8) -> i32 {
+        if self.out_of_time() {
+            return 0;
+        }
+        if board.is_terminal_state() {
+            return final_score(board, player);
+        }
+        if depth == 0 {
+            return self.evaluate_board(board, player);
+        }
+
+        let valid_moves = board.get_valid_moves_for_board(player);
+
+        if valid_moves.is_empty() {
+            // パスして相手のターン
+            return -self.negamax(board, depth - 1, 3 - player);
+        }
+
+        let mut best = -INFINITY;
+        for &(row, col) in &valid_moves {
+            let mut new_board = board.clone();
+            new_board.make_move_on_board(row, col, player);
+            best = best.max(-self.negamax(&new_board, depth - 1, 3 - player));
+        }
+        best
+    }
+
+    // アルファベータ法（ネガアルファ法）の実装（評価値は常に手番のプレイヤー player の視点）
+    pub(crate) fn alpha_beta(&self, board: &BoardState, depth: u8, mut alpha: i32, mut beta: i32, player: i8) -> i32 {
+        if self.out_of_time() {
+            return 0;
+        }
+        if board.is_terminal_state() {
+            return final_score(board, player);
+        }
+        if depth == 0 {
+            return self.evaluate_board(board, player);
+        }
+
+        let hash = board.hash(player);
+        if let Some(value) = self.probe_table(hash, depth, &mut alpha, &mut beta) {
+            return value;
+        }
+
+        let value = self.alpha_beta_children(board, depth, alpha, beta, player);
+        if !self.aborted.get() {
+            self.store_table(hash, depth, alpha, beta, value);
+        }
+        value
+    }
+
+    // アルファベータ法で子ノードを展開
+    fn alpha_beta_children(&self, board: &BoardState, depth: u8, mut alpha: i32, beta: i32, player: i8) -> i32 {
+        let valid_moves = board.get_valid_moves_for_board(player);
+
+        if valid_moves.is_empty() {
+            // パスして相手のターン
+            return -self.alpha_beta(board, depth - 1, -beta, -alpha, 3 - player);
+        }
+
+        let mut best = -INFINITY;
+        for &(row, col) in &valid_moves {
+            let mut new_board = board.clone();
+            new_board.make_move_on_board(row, col, player);
+            let eval = -self.alpha_beta(&new_board, depth - 1, -beta, -alpha, 3 - player);
+            best = best.max(eval);
+            alpha = alpha.max(eval);
+            if alpha >= beta {
+                break; // アルファベータカット
+            }
+        }
+        best
+    }
+
+    // ボード状態を次に打つプレイヤー player の視点で評価（改良版評価関数）
+    pub(crate) fn evaluate_board(&self, board: &BoardState, player: i8) -> i32 {
+        self.evaluate_breakdown(board, player, player).total()
+    }
+
+    // ボード状態を player の視点で評価し、項目ごとの内訳を返す（to_move は次に打つプレイヤー）。
+    // どの項目も両者の差なので、視点を入れ替えると符号だけが反転する
+    pub(crate) fn evaluate_breakdown(&self, board: &BoardState, player: i8, to_move: i8) -> EvalBreakdown {
+        let mut breakdown = EvalBreakdown::default();
+        let bitboard = Bitboard::from_board(board, player);
+
+        // 石の数による評価
+        let mut my_count = 0;
+        let mut opponent_count = 0;
+
+        // 位置による重み付け（戦略的）
+        let position_weights = [
+            [120, -20,  20,   5,   5,  20, -20, 120],
+            [-20, -40,  -5,  -5,  -5,  -5, -40, -20],
+            [ 20,  -5,  15,   3,   3,  15,  -5,  20],
+            [  5,  -5,   3,   3,   3,   3,  -5,   5],
+            [  5,  -5,   3,   3,   3,   3,  -5,   5],
+            [ 20,  -5,  15,   3,   3,  15,  -5,  20],
+            [-20, -40,  -5,  -5,  -5,  -5, -40, -20],
+            [120, -20,  20,   5,   5,  20, -20, 120],
+        ];
+
+        for (row, weights) in position_weights.iter().enumerate() {
+            for (col, &weight) in weights.iter().enumerate() {
+                match board.board[row][col] {
+                    piece if piece == player => {
+                        my_count += 1;
+                        breakdown.position += weight * self.weights.position;
+                    },
+                    piece if piece != 0 => {
+                        opponent_count += 1;
+                        breakdown.position -= weight * self.weights.position;
+                    },
+                    _ => {}
+                }
+            }
+        }
+
+        // 序盤・中盤・終盤で評価を調整
+        let total_pieces = my_count + opponent_count;
+
+        if total_pieces < 20 {
+            // 序盤: 位置を重視
+            breakdown.position *= 2;
+        } else if total_pieces < 50 {
+            // 中盤: モビリティ（機動性）を追加
+            let my_mobility = board.get_valid_moves_for_board(player).len() as i32;
+            let opponent_mobility = board.get_valid_moves_for_board(3 - player).len() as i32;
+            breakdown.mobility = (my_mobility - opponent_mobility) * self.weights.mobility;
+
+            // フロンティア（空きマスに接する石）は相手に着手の足場を与えるので少ない方が良い
+            let empty = !(bitboard.player | bitboard.opponent);
+            let next_to_empty = adjacent(empty);
+            let frontier = (bitboard.player & next_to_empty).count_ones() as i32 - (bitboard.opponent & next_to_empty).count_ones() as i32;
+            breakdown.frontier = -frontier * self.weights.frontier;
+
+            // 潜在的な着手可能数（相手の石に接する空きマス）は先々の打てる場所になる
+            let potential = (empty & adjacent(bitboard.opponent)).count_ones() as i32 - (empty & adjacent(bitboard.player)).count_ones() as i32;
+            breakdown.potential_mobility = potential * self.weights.potential_mobility;
+        } else {
+            // 終盤: 石の数を重視
+            breakdown.discs = (my_count - opponent_count) * self.weights.discs;
+
+            // パリティ: この先パスがなければ、空きマスが奇数のとき次に打つ側が最後の一手を打つ
+            let empties = 64 - total_pieces;
+            let last_move = if empties % 2 == 1 { to_move } else { 3 - to_move };
+            breakdown.parity = if last_move == player { self.weights.parity } else { -self.weights.parity };
+        }
+
+        // 個性による石数の差の評価（全局面）
+        breakdown.material = (my_count - opponent_count) * self.weights.material;
+
+        // 安定性の評価（角・辺・確定石）
+        self.evaluate_stability(board, player, bitboard, &mut breakdown);
+
+        // 空いている角の隣の石は角を取られる足場になる
+        self.evaluate_corner_danger(board, player, &mut breakdown);
+
+        breakdown
+    }
+
+    // 空いている角の X マス・C マスの石を評価
+    fn evaluate_corner_danger(&self, board: &BoardState, player: i8, breakdown: &mut EvalBreakdown) {
+        let owner = |(row, col): (usize, usize)| match board.board[row][col] {
+            piece if piece == player => 1,
+            0 => 0,
+            _ => -1,
+        };
+        for &(corner, x_square, c_squares) in &CORNER_SQUARES {
+            if board.board[corner.0][corner.1] != 0 {
+                continue;
+            }
+            breakdown.x_squares -= owner(x_square) * self.weights.x_squares;
+            for c_square in c_squares {
+                breakdown.c_squares -= owner(c_square) * self.weights.c_squares;
+            }
+        }
+    }
+
+    // 安定性を評価（角と辺の制御、確定石の数の差）
+    fn evaluate_stability(&self, board: &BoardState, player: i8, bitboard: Bitboard, breakdown: &mut EvalBreakdown) {
+        // 角の評価
+        let corners = [(0, 0), (0, 7), (7, 0), (7, 7)];
+        for &(row, col) in &corners {
+            match board.board[row][col] {
+                piece if piece == player => breakdown.corners += self.weights.corners,
+                piece if piece != 0 => breakdown.corners -= self.weights.corners,
+                _ => {}
+            }
+        }
+
+        // 辺の評価
+        let edges = self.weights.edges;
+        for i in 0..8 {
+            // 上辺と下辺
+            if board.board[0][i] == player { breakdown.edges += edges; }
+            else if board.board[0][i] != 0 { breakdown.edges -= edges; }
+
+            if board.board[7][i] == player { breakdown.edges += edges; }
+            else if board.board[7][i] != 0 { breakdown.edges -= edges; }
+
+            // 左辺と右辺
+            if board.board[i][0] == player { breakdown.edges += edges; }
+            else if board.board[i][0] != 0 { breakdown.edges -= edges; }
+
+            if board.board[i][7] == player { breakdown.edges += edges; }
+            else if board.board[i][7] != 0 { breakdown.edges -= edges; }
+        }
+
+        // 確定石の評価（辺の石でも返される可能性があるものは含めない）
+        if self.weights.stability != 0 {
+            let stable = bitboard.stable().count_ones() as i32 - bitboard.opponent_stable().count_ones() as i32;
+            breakdown.stability = stable * self.weights.stability;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::testing::random_positions;
+
+    // ルートの手番の視点のまま最大・最小を交互に取る素朴なミニマックス法
+    fn reference(search: &Search, board: &BoardState, depth: u8, player: i8) -> i32 {
+        if board.is_terminal_state() {
+            return final_score(board, search.player);
+        }
+        if depth == 0 {
+            return search.evaluate_breakdown(board, search.player, player).total();
+        }
+        let moves = board.get_valid_moves_for_board(player);
+        if moves.is_empty() {
+            return reference(search, board, depth - 1, 3 - player);
+        }
+        let values = moves.iter().map(|&(row, col)| {
+            let mut child = board.clone();
+            child.make_move_on_board(row, col, player);
+            reference(search, &child, depth - 1, 3 - player)
+        });
+        if player == search.player { values.max().unwrap() } else { values.min().unwrap() }
+    }
+
+    #[test]
+    fn evaluation_is_antisymmetric() {
+        let search = Search::with_weights(1, EvalWeights::default());
+        for (board, to_move) in random_positions(50, 2024) {
+            let black = search.evaluate_breakdown(&board, 1, to_move);
+            let white = search.evaluate_breakdown(&board, 2, to_move);
+            for ((name, value), (_, opposite)) in black.terms().into_iter().zip(white.terms()) {
+                assert_eq!(value, -opposite, "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn negamax_matches_root_perspective_minimax() {
+        for (board, player) in random_positions(25, 2024) {
+            let search = Search::with_weights(player, EvalWeights::default());
+            for depth in 1..=3 {
+                for (row, col) in board.get_valid_moves_for_board(player) {
+                    let mut child = board.clone();
+                    child.make_move_on_board(row, col, player);
+                    let expected = reference(&search, &child, depth - 1, 3 - player);
+                    assert_eq!(search.evaluate_move(&board, Strategy::Minimax(depth), row, col), expected);
+                    assert_eq!(search.evaluate_move(&board, Strategy::AlphaBeta(depth), row, col), expected);
+                }
+            }
+        }
+    }
+
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    #[test]
+    fn parallel_search_matches_sequential_search() {
+        for transcript in ["f5d6c3d3c4f4f6f3e6e7", "f5d6c5f4e3c6d3f6e6d7b7", "f5f6e6f4e3c5c4e7c6e2"] {
+            let mut game = crate::game::Game::new();
+            game.play_transcript(transcript).unwrap();
+            let (board, player) = (game.board_state(), game.current_player());
+            let moves = board.get_valid_moves_for_board(player);
+            for strategy in [Strategy::Minimax(3), Strategy::AlphaBeta(5)] {
+                let config = AiConfig::from(strategy);
+                let search = Search::with_options(player, Arc::new(TranspositionTable::new(TABLE_BITS)), config.weights, None);
+                let sequential: Vec<_> = moves.iter().map(|&(row, col)| ((row, col), search.evaluate_move(board, strategy, row, col))).collect();
+                let parallel = evaluate_moves_parallel(board, player, &config, &moves);
+                assert_eq!(parallel, sequential, "{:?}: {}", strategy, transcript);
+                assert_eq!(select_best_move(&parallel), select_best_move(&sequential));
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+// ベンチマーク（benches/engine.rs）用の入口
+//
+// 盤面（BoardState）と探索（Search）は crate の外に公開していないため、計測したい関数を
+// 同じ名前・同じ引数のまま薄く包んで呼べるようにする。ベンチマーク以外からは使わない。
+
+use crate::ai::{EvalWeights, Search};
+use crate::board::BoardState;
+
+// alpha_beta を全幅の窓（-INFINITY, INFINITY）で呼ぶときの境界
+pub const INFINITY: i32 = crate::ai::INFINITY;
+
+// 局面（盤面と手番）
+#[derive(Clone)]
+pub struct Position {
+    board: BoardState,
+    player: i8,
+}
+
+impl Position {
+    // 局面文字列（64マス分の X / O / - と手番）から作成
+    pub fn from_position(position: &str) -> Result<Self, String> {
+        let (board, player) = BoardState::from_position_string(position)?;
+        Ok(Position { board, player })
+    }
+
+    // 手番のプレイヤー（1: 黒, 2: 白）
+    pub fn player(&self) -> i8 {
+        self.player
+    }
+
+    pub fn get_valid_moves_for_board(&self, player: i8) -> Vec<(usize, usize)> {
+        self.board.get_valid_moves_for_board(player)
+    }
+
+    pub fn make_move_on_board(&mut self, row: usize, col: usize, player: i8) {
+        self.board.make_move_on_board(row, col, player);
+    }
+}
+
+// 既定の評価関数の重みでの探索（置換表を持つため、同じ局面を計測し直すときは作り直す）
+pub struct Engine {
+    search: Search,
+}
+
+impl Engine {
+    pub fn new(player: i8) -> Self {
+        Engine {
+            search: Search::with_weights(player, EvalWeights::default()),
+        }
+    }
+
+    pub fn evaluate_board(&self, position: &Position, player: i8) -> i32 {
+        self.search.evaluate_board(&position.board, player)
+    }
+
+    pub fn alpha_beta(&self, position: &Position, depth: u8, alpha: i32, beta: i32, player: i8) -> i32 {
+        self.search.alpha_beta(&position.board, depth, alpha, beta, player)
+    }
+}
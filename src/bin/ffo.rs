@@ -0,0 +1,205 @@
+// FFO 終盤テスト局面による完全読みのベンチマーク
+//
+// 使い方: ffo [--file 問題ファイル] [--first 番号] [--table-bits ビット数] [--quick] [番号 | 番号-番号]...
+// FFO（Fforum）の終盤テスト局面を完全読みで解き、石差・最善手・探索局面数・時間・NPS を表示する。
+// 既知の石差と違う局面があれば終了コード 1 で終わるため、探索を速くする変更が正しいかも確かめられる。
+// 組み込みの局面は、解き直して FFO の公開値（石差・最善手）と一致することを確かめた #40〜#42 と #45。
+// 局面を写し間違えると別の問題の正解を測ることになるため、確かめていない局面は組み込まない。
+// #40〜#59 をすべて解くには、Edax の problem/fforum-40-59.obf のような
+// 「64 マスの X / O / - と手番; 最善手:石差; ...」形式のファイルを --file で指定する（1行目が --first の番号、既定は 40）。
+// 番号を省略した場合はすべての局面を解く。--quick は CI 用に数秒で解ける局面（QUICK）だけを解く。
+
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use mini_wasm_othello::endgame::Solver;
+use mini_wasm_othello::game::square_name;
+use mini_wasm_othello::Game;
+
+const USAGE: &str = "使い方: ffo [--file 問題ファイル] [--first 番号] [--table-bits ビット数] [--quick] [番号 | 番号-番号]...";
+
+// (番号, 局面文字列, 手番側から見た石差)。石差・最善手は FFO の公開値と一致することを確かめてある
+const BUILTIN: &[(u32, &str, i32)] = &[
+    (40, "O--OOOOX-OOOOOOXOOXXOOOXOOXOOOXXOOOOOOXX---OOOOX----O--X-------- X", 38),
+    (41, "-OOOOO----OOOOX--OOOOOO-XXXXXOO--XXOOX--OOXOXX----OXXO---OOO--O- X", 0),
+    (42, "--OOO-------XX-OOOOOOXOO-OOOOXOOX-OOOXXO---OOXOO---OOOXO--OOOO-- X", 6),
+    (45, "---XXXX-X-XXXO--XXOXOO--XXXOXO--XXOXXO---OXXXOO-O-OOOO------OO-- X", 6),
+];
+
+// --quick で解く局面の番号（#40 は空き 20 で、1 コアで 5 秒ほど）
+const QUICK: &[u32] = &[40];
+
+struct Problem {
+    number: u32,
+    position: String,
+    expected: Option<i32>, // 既知の石差（ファイルに書かれていない場合は None）
+}
+
+struct Options {
+    file: Option<String>,
+    first: u32,
+    table_bits: u32,
+    quick: bool,
+    ranges: Vec<(u32, u32)>,
+}
+
+fn parse_number(text: &str) -> Result<u32, String> {
+    text.trim().parse().map_err(|_| format!("不正な番号です: {}", text))
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        file: None,
+        first: 40,
+        table_bits: 21,
+        quick: false,
+        ranges: Vec::new(),
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} の値がありません", arg));
+        match arg.as_str() {
+            "-f" | "--file" => options.file = Some(value()?),
+            "--first" => options.first = parse_number(&value()?)?,
+            "--table-bits" => {
+                options.table_bits = match value()?.parse() {
+                    Ok(bits @ 10..=28) => bits,
+                    _ => return Err("置換表のビット数は 10〜28 で指定してください".to_string()),
+                }
+            }
+            "-q" | "--quick" => options.quick = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            other if other.starts_with('-') => return Err(format!("不明な引数です: {}\n{}", other, USAGE)),
+            range => {
+                let (first, last) = range.split_once('-').unwrap_or((range, range));
+                options.ranges.push((parse_number(first)?, parse_number(last)?));
+            }
+        }
+    }
+    Ok(options)
+}
+
+// 「局面; 手:石差; 手:石差; ...」形式の1行を読む（最初の手の石差を既知の石差とする）
+fn parse_problem(number: u32, line: &str) -> Result<Problem, String> {
+    let mut fields = line.split(';');
+    let position = fields.next().unwrap_or_default().trim().to_string();
+    Game::from_position(&position).map_err(|e| format!("#{}: {}", number, e))?;
+
+    let expected = match fields.next().map(str::trim).filter(|field| !field.is_empty()) {
+        Some(field) => {
+            let (_, score) = field.split_once(':').ok_or_else(|| format!("#{}: 不正な石差です: {}", number, field))?;
+            Some(score.trim().parse().map_err(|_| format!("#{}: 不正な石差です: {}", number, field))?)
+        }
+        None => None,
+    };
+    Ok(Problem { number, position, expected })
+}
+
+fn load_problems(options: &Options) -> Result<Vec<Problem>, String> {
+    let problems = match &options.file {
+        Some(path) => {
+            let text = std::fs::read_to_string(path).map_err(|e| format!("{} を読み込めません: {}", path, e))?;
+            text.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .zip(options.first..)
+                .map(|(line, number)| parse_problem(number, line))
+                .collect::<Result<Vec<_>, _>>()?
+        }
+        None => BUILTIN
+            .iter()
+            .map(|&(number, position, expected)| Problem {
+                number,
+                position: position.to_string(),
+                expected: Some(expected),
+            })
+            .collect(),
+    };
+
+    Ok(problems
+        .into_iter()
+        .filter(|problem| !options.quick || QUICK.contains(&problem.number))
+        .filter(|problem| options.ranges.is_empty() || options.ranges.iter().any(|&(first, last)| (first..=last).contains(&problem.number)))
+        .collect())
+}
+
+fn nodes_per_second(nodes: u64, elapsed: Duration) -> f64 {
+    nodes as f64 / elapsed.as_secs_f64().max(1e-9)
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+    let problems = match load_problems(&options) {
+        Ok(problems) if !problems.is_empty() => problems,
+        Ok(_) => {
+            eprintln!("解く局面がありません");
+            return ExitCode::FAILURE;
+        }
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!("  番号  空き  石差  期待値  最善手        局面数      時間      NPS");
+    let mut total_nodes = 0;
+    let mut total_time = Duration::ZERO;
+    let mut wrong = Vec::new();
+    for problem in &problems {
+        let game = Game::from_position(&problem.position).expect("読み込み時に確認済み");
+        let empties = problem.position.chars().take(64).filter(|&c| c == '-').count();
+
+        let mut solver = Solver::with_table_bits(options.table_bits);
+        let start = Instant::now();
+        let solution = solver.solve(&game);
+        let elapsed = start.elapsed();
+        total_nodes += solver.nodes();
+        total_time += elapsed;
+
+        let verdict = match problem.expected {
+            Some(expected) if expected != solution.score => {
+                wrong.push(problem.number);
+                "不正解"
+            }
+            Some(_) => "",
+            None => "（期待値なし）",
+        };
+        println!(
+            "  #{:<3}  {:4}  {:+4}  {:>6}  {:>6}  {:>14}  {:7.2}秒  {:7.2}M  {}",
+            problem.number,
+            empties,
+            solution.score,
+            problem.expected.map_or("-".to_string(), |expected| format!("{:+}", expected)),
+            solution.best_move.map_or("パス".to_string(), |(row, col)| square_name(row, col)),
+            solver.nodes(),
+            elapsed.as_secs_f64(),
+            nodes_per_second(solver.nodes(), elapsed) / 1e6,
+            verdict
+        );
+    }
+
+    println!(
+        "合計: {} 局面  局面数 {}  時間 {:.2}秒  NPS {:.2}M",
+        problems.len(),
+        total_nodes,
+        total_time.as_secs_f64(),
+        nodes_per_second(total_nodes, total_time) / 1e6
+    );
+    if wrong.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        let numbers: Vec<String> = wrong.iter().map(|number| format!("#{}", number)).collect();
+        eprintln!("石差が既知の値と異なる局面: {}", numbers.join(", "));
+        ExitCode::FAILURE
+    }
+}
@@ -0,0 +1,212 @@
+// GTP（囲碁の Go Text Protocol）に似た行単位のプロトコルで動作する思考エンジン
+//
+// 汎用の対局ハーネスや回帰テスト用スクリプトから操作するためのもの。
+// 応答は GTP と同じく成功時 "= 結果"、失敗時 "? 理由" で、空行で終わる（コマンドに番号を付けた場合は番号も返す）。
+// 色は b / black / w / white、着手は f5 のような座標か pass で指定する。
+//
+// 使い方: gtp [--difficulty easy|medium|hard|expert]
+
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+
+use mini_wasm_othello::game::{parse_square, square_name};
+use mini_wasm_othello::{AiDifficulty, Game};
+
+const COMMANDS: [&str; 15] = [
+    "protocol_version",
+    "name",
+    "version",
+    "known_command",
+    "list_commands",
+    "boardsize",
+    "clear_board",
+    "komi",
+    "play",
+    "genmove",
+    "undo",
+    "showboard",
+    "final_score",
+    "set_difficulty",
+    "quit",
+];
+
+// コマンドの処理結果
+enum Reply {
+    Success(String),
+    Failure(String),
+    Quit,
+}
+
+// エンジンの状態
+struct Engine {
+    game: Game,
+    difficulty: AiDifficulty,
+}
+
+impl Engine {
+    fn handle(&mut self, command: &str, args: &[&str]) -> Reply {
+        match command {
+            "protocol_version" => Reply::Success("2".to_string()),
+            "name" => Reply::Success("mini-wasm-othello".to_string()),
+            "version" => Reply::Success(env!("CARGO_PKG_VERSION").to_string()),
+            "known_command" => Reply::Success(args.first().is_some_and(|name| COMMANDS.contains(name)).to_string()),
+            "list_commands" => Reply::Success(COMMANDS.join("\n")),
+            "boardsize" => match args.first() {
+                Some(&"8") => Reply::Success(String::new()),
+                _ => Reply::Failure("unacceptable size".to_string()),
+            },
+            "clear_board" => {
+                self.game = Game::new();
+                Reply::Success(String::new())
+            }
+            "komi" => Reply::Success(String::new()), // オセロにはコミがないので無視する
+            "play" => match args {
+                [color, square] => self.play(color, square),
+                _ => Reply::Failure("syntax error".to_string()),
+            },
+            "genmove" => match args {
+                [color] => self.genmove(color),
+                _ => Reply::Failure("syntax error".to_string()),
+            },
+            "undo" => match self.game.undo() {
+                Some(_) => Reply::Success(String::new()),
+                None => Reply::Failure("cannot undo".to_string()),
+            },
+            "showboard" => {
+                let (black, white) = self.game.score();
+                Reply::Success(format!("\n{}X: {}  O: {}", self.game.to_ascii(false), black, white))
+            }
+            "final_score" => {
+                let (black, white) = self.game.score();
+                Reply::Success(match black.cmp(&white) {
+                    std::cmp::Ordering::Greater => format!("B+{}", black - white),
+                    std::cmp::Ordering::Less => format!("W+{}", white - black),
+                    std::cmp::Ordering::Equal => "0".to_string(),
+                })
+            }
+            "set_difficulty" => match args.first().map(|name| name.parse::<AiDifficulty>()) {
+                Some(Ok(difficulty)) => {
+                    self.difficulty = difficulty;
+                    Reply::Success(String::new())
+                }
+                Some(Err(message)) => Reply::Failure(message),
+                None => Reply::Failure("syntax error".to_string()),
+            },
+            "quit" => Reply::Quit,
+            _ => Reply::Failure("unknown command".to_string()),
+        }
+    }
+
+    // 指定した色の着手（またはパス）を適用
+    fn play(&mut self, color: &str, square: &str) -> Reply {
+        let Some(player) = parse_color(color) else {
+            return Reply::Failure("invalid color".to_string());
+        };
+
+        if square.eq_ignore_ascii_case("pass") {
+            // 手番の受け渡しは Game が自動で行うため、手番でない色のパスは何もせずに受け付ける
+            return if self.must_pass(player) {
+                Reply::Success(String::new())
+            } else {
+                Reply::Failure("illegal move".to_string())
+            };
+        }
+
+        let Some((row, col)) = parse_square(square) else {
+            return Reply::Failure("invalid coordinate".to_string());
+        };
+        if self.game.current_player() != player {
+            return Reply::Failure("illegal move".to_string());
+        }
+        match self.game.play(row, col) {
+            Ok(()) => Reply::Success(String::new()),
+            Err(_) => Reply::Failure("illegal move".to_string()),
+        }
+    }
+
+    // 指定した色の手を AI に選ばせて打つ
+    fn genmove(&mut self, color: &str) -> Reply {
+        let Some(player) = parse_color(color) else {
+            return Reply::Failure("invalid color".to_string());
+        };
+        if self.must_pass(player) {
+            return Reply::Success("pass".to_string());
+        }
+        if self.game.current_player() != player {
+            return Reply::Failure("not your turn".to_string());
+        }
+
+        match self.game.ai_move(self.difficulty) {
+            Some((row, col)) if self.game.play(row, col).is_ok() => Reply::Success(square_name(row, col)),
+            _ => Reply::Failure("no move".to_string()),
+        }
+    }
+
+    // 指定した色に有効な手がないか（対局終了後も含む）
+    fn must_pass(&self, player: i8) -> bool {
+        !self.game.has_moves(player)
+    }
+}
+
+fn parse_color(color: &str) -> Option<i8> {
+    match color.to_ascii_lowercase().as_str() {
+        "b" | "black" => Some(1),
+        "w" | "white" => Some(2),
+        _ => None,
+    }
+}
+
+fn main() -> ExitCode {
+    let mut difficulty = AiDifficulty::Expert;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("-d" | "--difficulty", Some(value)) => match value.parse() {
+                Ok(value) => difficulty = value,
+                Err(message) => {
+                    eprintln!("{}", message);
+                    return ExitCode::FAILURE;
+                }
+            },
+            _ => {
+                eprintln!("使い方: gtp [--difficulty easy|medium|hard|expert]");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let mut engine = Engine {
+        game: Game::new(),
+        difficulty,
+    };
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        // コメント（# 以降）を除いてコマンドに分割する
+        let line = line.split('#').next().unwrap_or("");
+        let mut words: Vec<&str> = line.split_whitespace().collect();
+        if words.is_empty() {
+            continue;
+        }
+        let id = if words[0].chars().all(|c| c.is_ascii_digit()) { Some(words.remove(0)) } else { None };
+        let Some((&command, args)) = words.split_first() else {
+            continue;
+        };
+
+        let reply = engine.handle(&command.to_ascii_lowercase(), args);
+        let id = id.unwrap_or("");
+        let written = match &reply {
+            Reply::Success(text) if !text.is_empty() => writeln!(out, "={} {}\n", id, text),
+            Reply::Success(_) | Reply::Quit => writeln!(out, "={}\n", id),
+            Reply::Failure(message) => writeln!(out, "?{} {}\n", id, message),
+        };
+        if written.and_then(|_| out.flush()).is_err() || matches!(reply, Reply::Quit) {
+            break;
+        }
+    }
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,277 @@
+// NBoard プロトコルで動作する思考エンジン
+//
+// 標準入力から NBoard のコマンド（nboard, set game, set depth, move, go, hint, ping など）を読み、
+// 標準出力に応答を返す。NBoard などの GUI にエンジンとして登録すると、他のエンジンと対局・比較できる。
+// 評価値は探索の評価値（手番のプレイヤーの視点）を予想される最終石差に換算して返す。
+// set depth の深さは MAX_DEPTH までとし、1手ごとに持ち時間（--time、既定 5 秒）内で読めた最も深い結果を使う。
+//
+// 使い方: nboard [--difficulty easy|medium|hard|expert] [--time ミリ秒]
+
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use mini_wasm_othello::game::{parse_square, square_name};
+use mini_wasm_othello::review::eval_to_discs;
+use mini_wasm_othello::{AiConfig, AiDifficulty, Game, Strategy};
+
+const ENGINE_NAME: &str = "mini-wasm-othello";
+
+const USAGE: &str = "使い方: nboard [--difficulty easy|medium|hard|expert] [--time ミリ秒]";
+
+// set depth で指定できる深さの上限（NBoard は起動時に 20 以上を送ることが多い）
+const MAX_DEPTH: u8 = 20;
+
+// 1手あたりの既定の持ち時間（ミリ秒）
+const DEFAULT_TIME: u64 = 5000;
+
+// エンジンの状態
+struct Engine {
+    game: Game,
+    side: i8, // プロトコル上の手番（パスの手番も含む。Game はパスを自動で処理する）
+    config: AiConfig, // 探索の深さと1手あたりの持ち時間
+}
+
+impl Engine {
+    fn new(config: AiConfig) -> Self {
+        let game = Game::new();
+        let side = game.current_player();
+        Engine { game, side, config }
+    }
+
+    // 1行分のコマンドを処理し、応答を出力する
+    fn handle(&mut self, line: &str, out: &mut impl Write) -> io::Result<()> {
+        let line = line.trim();
+        let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+
+        match command {
+            "nboard" => writeln!(out, "set myname {}", ENGINE_NAME)?,
+            "set" => {
+                let (name, value) = rest.trim().split_once(' ').unwrap_or((rest.trim(), ""));
+                match name {
+                    "game" => {
+                        if let Err(message) = self.set_game(value) {
+                            eprintln!("{}", message);
+                        }
+                    }
+                    "depth" => match value.trim().parse::<u8>() {
+                        Ok(depth) => self.config.strategy = Strategy::AlphaBeta(depth.clamp(1, MAX_DEPTH)),
+                        Err(_) => eprintln!("不正な深さです: {}", value),
+                    },
+                    _ => {} // contempt など未対応の設定は無視する
+                }
+            }
+            "move" => {
+                if let Err(message) = self.play(rest) {
+                    eprintln!("{}", message);
+                }
+            }
+            "go" => {
+                let started = Instant::now();
+                let reply = match self.best_moves().first() {
+                    Some(&((row, col), score)) => {
+                        format!("{}/{}/{:.2}", square_name(row, col).to_uppercase(), eval_to_discs(score), started.elapsed().as_secs_f64())
+                    }
+                    None => "PA".to_string(),
+                };
+                writeln!(out, "=== {}", reply)?;
+            }
+            "hint" => {
+                let count = rest.trim().parse::<usize>().unwrap_or(1).max(1);
+                writeln!(out, "status 思考中")?;
+                let depth = match self.config.strategy {
+                    Strategy::Greedy => 1,
+                    Strategy::Minimax(depth) | Strategy::AlphaBeta(depth) => depth,
+                };
+                for ((row, col), score) in self.best_moves().into_iter().take(count) {
+                    writeln!(out, "search {} {} 0 {}", square_name(row, col).to_uppercase(), eval_to_discs(score), depth)?;
+                }
+                writeln!(out, "status")?;
+            }
+            "ping" => writeln!(out, "pong {}", rest.trim())?,
+            "learn" => writeln!(out, "learned")?,
+            "quit" => return Err(io::Error::new(io::ErrorKind::Interrupted, "quit")),
+            "" => {}
+            _ => eprintln!("未対応のコマンドです: {}", line),
+        }
+        out.flush()
+    }
+
+    // GGF 形式の棋譜（BO[...] の開始局面と B[...] / W[...] の着手）で局面を設定
+    fn set_game(&mut self, ggf: &str) -> Result<(), String> {
+        let board = tag_value(ggf, "BO").ok_or("開始局面 (BO) がありません")?;
+        // "8 <64マス> <手番>" の形式
+        let position = board.trim().strip_prefix('8').ok_or_else(|| format!("8x8 以外の盤面には対応していません: {}", board))?;
+
+        // 途中の着手が不正な場合は元の局面を残すため、別のエンジンで再生してから置き換える
+        let mut engine = Engine {
+            game: Game::from_position(position)?,
+            side: match position.trim().chars().last() {
+                Some('O') | Some('o') => 2,
+                _ => 1,
+            },
+            config: self.config,
+        };
+        for mv in moves(ggf) {
+            engine.play(&mv)?;
+        }
+        *self = engine;
+        Ok(())
+    }
+
+    // 着手（"F5", "F5/1.23/0.5" または パスの "PA"）を適用
+    fn play(&mut self, text: &str) -> Result<(), String> {
+        let square = text.trim().split('/').next().unwrap_or("");
+        if !square.eq_ignore_ascii_case("PA") {
+            if self.game.current_player() != self.side {
+                return Err(format!("パスするべき手番に着手されました: {}", square));
+            }
+            let (row, col) = parse_square(square).ok_or_else(|| format!("不正な着手です: {}", text))?;
+            self.game.play(row, col)?;
+        }
+        self.side = 3 - self.side;
+        Ok(())
+    }
+
+    // 各有効手を評価値の高い順に並べる（プロトコル上の手番がパスの場合は空）
+    fn best_moves(&self) -> Vec<((usize, usize), i32)> {
+        if self.game.current_player() != self.side {
+            return Vec::new();
+        }
+        let mut moves = self.game.evaluate_moves_with(self.config);
+        moves.sort_by_key(|&(_, score)| std::cmp::Reverse(score)); // 安定ソートなので同点の場合は先に見つかった手が先になる
+        moves
+    }
+}
+
+// GGF のタグ（TAG[値]）の値を取得
+fn tag_value<'a>(ggf: &'a str, tag: &str) -> Option<&'a str> {
+    tags(ggf).find(|(name, _)| *name == tag).map(|(_, value)| value)
+}
+
+// GGF の着手（B[...] / W[...]）を順に取り出す
+fn moves(ggf: &str) -> Vec<String> {
+    tags(ggf)
+        .filter(|(name, _)| *name == "B" || *name == "W")
+        .map(|(_, value)| value.to_string())
+        .collect()
+}
+
+// GGF のタグを (名前, 値) の組として順に取り出す
+fn tags(ggf: &str) -> impl Iterator<Item = (&str, &str)> {
+    let mut rest = ggf;
+    std::iter::from_fn(move || {
+        let open = rest.find('[')?;
+        let close = open + rest[open..].find(']')?;
+        let name_start = rest[..open]
+            .rfind(|c: char| !c.is_ascii_uppercase())
+            .map_or(0, |index| index + 1);
+        let tag = (&rest[name_start..open], &rest[open + 1..close]);
+        rest = &rest[close + 1..];
+        Some(tag)
+    })
+}
+
+fn main() -> ExitCode {
+    let mut difficulty = AiDifficulty::Expert;
+    let mut time = DEFAULT_TIME;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("-d" | "--difficulty", Some(value)) => match value.parse() {
+                Ok(value) => difficulty = value,
+                Err(message) => {
+                    eprintln!("{}", message);
+                    return ExitCode::FAILURE;
+                }
+            },
+            ("-t" | "--time", Some(value)) => match value.parse() {
+                Ok(value) if value > 0 => time = value,
+                _ => {
+                    eprintln!("不正な持ち時間です: {}", value);
+                    return ExitCode::FAILURE;
+                }
+            },
+            _ => {
+                eprintln!("{}", USAGE);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let config = AiConfig {
+        time_limit: Some(Duration::from_millis(time)),
+        ..difficulty.into()
+    };
+    let mut engine = Engine::new(config);
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if engine.handle(&line, &mut out).is_err() {
+            break;
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // c8 に打つと白がパスになる局面
+    const PASS_POSITION: &str = "8 OOOOOOOX--OOOOOX-OOOOXXX--OXXOXXOOOOOOOX-OXOXOXX-OOXXXXXXO-OXXOO *";
+
+    fn handle(engine: &mut Engine, line: &str) -> String {
+        let mut out = Vec::new();
+        engine.handle(line, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn tags_and_moves_are_parsed_in_order() {
+        let ggf = "(;GM[Othello]PC[NBoard]PB[me]PW[you]BO[8 ---------------------------O*------*O--------------------------- *]B[F5//0.5]W[d6]B[C3/1.0];)";
+        let tags: Vec<(&str, &str)> = tags(ggf).collect();
+        assert_eq!(tags[..4], [("GM", "Othello"), ("PC", "NBoard"), ("PB", "me"), ("PW", "you")]);
+        assert_eq!(tag_value(ggf, "PW"), Some("you"));
+        assert_eq!(tag_value(ggf, "RE"), None);
+        assert_eq!(moves(ggf), vec!["F5//0.5", "d6", "C3/1.0"]);
+
+        let mut engine = Engine::new(AiDifficulty::Easy.into());
+        engine.set_game(ggf).unwrap();
+        assert_eq!(engine.game.transcript(), "f5d6c3");
+        assert_eq!(engine.side, 2);
+    }
+
+    #[test]
+    fn pass_moves_switch_the_side() {
+        let mut engine = Engine::new(AiDifficulty::Easy.into());
+        engine.set_game(&format!("(;GM[Othello]BO[{}]B[C8]W[PA];)", PASS_POSITION)).unwrap();
+        assert_eq!((engine.game.current_player(), engine.side), (1, 1));
+
+        // パスするべき手番では go は PA を返し、PA 以外の着手は受け付けない
+        let mut engine = Engine::new(AiDifficulty::Easy.into());
+        engine.set_game(&format!("(;GM[Othello]BO[{}]B[C8];)", PASS_POSITION)).unwrap();
+        assert_eq!(handle(&mut engine, "go"), "=== PA\n");
+        assert!(engine.play("A1").is_err());
+        handle(&mut engine, "move PA");
+        assert_eq!(engine.side, 1);
+    }
+
+    #[test]
+    fn invalid_game_keeps_the_previous_position() {
+        let mut engine = Engine::new(AiDifficulty::Easy.into());
+        engine.set_game("(;GM[Othello]BO[8 ---------------------------O*------*O--------------------------- *]B[F5];)").unwrap();
+        for ggf in [
+            "(;GM[Othello]BO[8 ---------------------------O*------*O--------------------------- *]B[F5]W[A1];)",
+            "(;GM[Othello]BO[10 ----];)",
+            "(;GM[Othello]B[F5];)",
+        ] {
+            assert!(engine.set_game(ggf).is_err(), "{}", ggf);
+            assert_eq!((engine.game.transcript().as_str(), engine.side), ("f5", 2), "{}", ggf);
+        }
+    }
+}
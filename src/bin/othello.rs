@@ -0,0 +1,400 @@
+// 端末で AI と対局するコマンドラインツール
+//
+// 使い方: othello [--difficulty easy|medium|hard|expert | --strength 0〜100 | --elo レーティング]
+//                 [--personality 個性] [--seed シード | --no-variety] [--adaptive <状態ファイル>] [--lang ja|en]
+//                 [--color black|white] [--position <局面文字列>] [--moves <棋譜>]
+// 標準入力から1行ずつコマンドを読むため、SSH 越しやスクリプトからの入力でも対局できる。
+// AI は定石と最善に近い手から無作為に選ぶため毎局違う手順になる（--seed で再現、--no-variety で常に最善手）。
+// --adaptive を指定すると AI の強さをプレイヤーの結果と着手の精度に合わせて調整し、状態をファイルに保存する。
+// AI の手とヒントには、その手を選んだ理由の説明を表示する（--lang で日本語・英語を切り替え）。
+
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use mini_wasm_othello::explain::Language;
+use mini_wasm_othello::game::{parse_square, square_name};
+use mini_wasm_othello::perft;
+
+use mini_wasm_othello::{AdaptiveStrength, AiConfig, AiDifficulty, Game, Personality, Strength};
+
+const HELP: &str = "\
+コマンド:
+  d3               指定したマスに石を置く
+  undo             自分の直前の手まで取り消す
+  hint             AI のおすすめの手とその理由を表示
+  moves            有効な手の一覧を表示
+  stable           確定石（二度と返されない石）を表示
+  eval             現在の局面の評価値の内訳を手番のプレイヤーの視点で表示
+  perft <深さ>     現在の局面から指定した深さまでの末端局面の数を手ごとに表示
+  board            盤面を表示
+  difficulty <名>  AI の難易度を変更 (easy / medium / hard / expert)
+  strength <値>    AI の強さを 0〜100 で指定
+  elo <値>         AI の強さを推定レーティングで指定
+  personality <名> AI の個性を変更 (balanced / aggressive / corner-hungry / mobility-minded)
+  transcript       棋譜を表示
+  review           これまでの手を AI の最善手と比べて振り返る
+  graph            評価値の推移をグラフで表示
+  save <ファイル>  棋譜をファイルに保存
+  help             このヘルプを表示
+  quit             終了";
+
+// コマンドライン引数で指定する設定
+struct Options {
+    difficulty: AiDifficulty,
+    strength: Option<Strength>, // 指定した場合は難易度の代わりに使う
+    personality: Personality,
+    seed: Option<u64>, // 手のばらつきに使う乱数のシード（None なら常に最善手）
+    adaptive: Option<(AdaptiveStrength, PathBuf)>, // 強さの自動調整の状態と保存先（指定した場合は難易度・強さの代わりに使う）
+    language: Language, // 手の説明の言語
+    human: i8, // 1: 黒, 2: 白
+    game: Game,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        difficulty: AiDifficulty::Medium,
+        strength: None,
+        personality: Personality::Balanced,
+        seed: Some(time_seed()),
+        adaptive: None,
+        language: Language::Japanese,
+        human: 1,
+        game: Game::new(),
+    };
+    let mut moves = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} の値がありません", arg));
+        match arg.as_str() {
+            "-d" | "--difficulty" => options.difficulty = value()?.parse()?,
+            "-s" | "--strength" => options.strength = Some(Strength::new(parse_number(&value()?)?)),
+            "--elo" => options.strength = Some(Strength::from_elo(parse_number(&value()?)?)),
+            "--personality" => options.personality = value()?.parse()?,
+            "--seed" => {
+                let seed = value()?;
+                options.seed = Some(seed.parse().map_err(|_| format!("不正なシードです: {}", seed))?);
+            }
+            "--no-variety" => options.seed = None,
+            "--adaptive" => {
+                let path = PathBuf::from(value()?);
+                let state = match std::fs::read_to_string(&path) {
+                    Ok(text) => text.parse()?,
+                    Err(_) => AdaptiveStrength::new(), // 初回はファイルがないので中級の強さから始める
+                };
+                options.adaptive = Some((state, path));
+            }
+            "--lang" => options.language = value()?.parse()?,
+            "-c" | "--color" => {
+                options.human = match value()?.to_ascii_lowercase().as_str() {
+                    "black" | "b" | "x" => 1,
+                    "white" | "w" | "o" => 2,
+                    other => return Err(format!("不明な色です: {}", other)),
+                }
+            }
+            "-p" | "--position" => options.game = Game::from_position(&value()?)?,
+            "-m" | "--moves" => moves = Some(value()?),
+            "-h" | "--help" => {
+                println!("使い方: othello [--difficulty easy|medium|hard|expert | --strength 0〜100 | --elo レーティング] [--personality 個性] [--seed シード | --no-variety] [--adaptive <状態ファイル>] [--lang ja|en] [--color black|white] [--position <局面>] [--moves <棋譜>]\n\n{}", HELP);
+                std::process::exit(0);
+            }
+            other => return Err(format!("不明な引数です: {}", other)),
+        }
+    }
+
+    if let Some(moves) = moves {
+        options.game.play_transcript(&moves)?;
+    }
+    Ok(options)
+}
+
+fn parse_number(text: &str) -> Result<f64, String> {
+    text.trim().parse().map_err(|_| format!("不正な数値です: {}", text))
+}
+
+// 現在時刻から作る乱数のシード
+fn time_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+}
+
+impl Options {
+    // AI の設定（強さを指定した場合は強さから、そうでなければ難易度から決め、個性を加える）
+    fn hint_config(&self) -> AiConfig {
+        let config = match (&self.adaptive, self.strength) {
+            (Some((adaptive, _)), _) => adaptive.config(),
+            (None, Some(strength)) => strength.config(),
+            (None, None) => self.difficulty.into(),
+        };
+        AiConfig { weights: self.personality.weights(), ..config }
+    }
+
+    // AI が打つ手の設定（ヒントの設定に手のばらつきを加える）
+    fn ai_config(&self) -> AiConfig {
+        match self.seed {
+            Some(seed) => self.hint_config().with_variety(seed),
+            None => self.hint_config(),
+        }
+    }
+
+    fn ai_description(&self) -> String {
+        let level = match (&self.adaptive, self.strength) {
+            (Some((adaptive, _)), _) => {
+                let strength = adaptive.strength();
+                format!("自動調整 強さ {:.0} (推定レーティング {:.0}、{} 局)", strength.value(), strength.elo(), adaptive.games)
+            }
+            (None, Some(strength)) => format!("強さ {:.0} (推定レーティング {:.0})", strength.value(), strength.elo()),
+            (None, None) => self.difficulty.description().to_string(),
+        };
+        format!("{}・{}", level, self.personality.description())
+    }
+
+    // 強さの自動調整の状態をファイルに保存
+    fn save_adaptive(&self) {
+        if let Some((adaptive, path)) = &self.adaptive {
+            if let Err(error) = std::fs::write(path, format!("{}\n", adaptive)) {
+                println!("強さの自動調整の状態を保存できませんでした: {}", error);
+            }
+        }
+    }
+}
+
+fn player_name(player: i8) -> &'static str {
+    if player == 1 { "黒 (X)" } else { "白 (O)" }
+}
+
+// 盤面と石の数、手番を表示
+fn print_status(game: &Game, human: i8) {
+    let (black, white) = game.score();
+    println!();
+    print!("{}", game.to_ascii(game.current_player() == human));
+    println!("黒 (X): {}  白 (O): {}", black, white);
+
+    if game.is_game_over() {
+        let result = match black.cmp(&white) {
+            std::cmp::Ordering::Greater => "黒の勝ち",
+            std::cmp::Ordering::Less => "白の勝ち",
+            std::cmp::Ordering::Equal => "引き分け",
+        };
+        println!("対局終了: {} ({}-{})  棋譜: {}", result, black, white, game.transcript());
+    } else {
+        println!("{} の番です", player_name(game.current_player()));
+    }
+}
+
+// AI の手番が続く限り AI に打たせる
+fn play_ai_turns(game: &mut Game, options: &Options) {
+    while !game.is_game_over() && game.current_player() != options.human {
+        let config = options.ai_config();
+        let Some((row, col)) = game.ai_move_with(config) else {
+            break;
+        };
+        let player = game.current_player();
+        let explanation = game.explain_move_with(row, col, options.language, config.weights);
+        if game.play(row, col).is_ok() {
+            println!("AI {}: {}", player_name(player), square_name(row, col));
+            if let Some(explanation) = explanation {
+                println!("  {}", explanation);
+            }
+        }
+    }
+}
+
+// 各手の分類と損失、集計を表示
+fn print_review(game: &Game) {
+    let review = game.review();
+    for review in &review.moves {
+        let note = if review.disc_loss > 0 {
+            format!(" (-{} 石、最善は {})", review.disc_loss, square_name(review.best.0, review.best.1))
+        } else {
+            String::new()
+        };
+        println!("{:3}. {} {}  {}{}", review.ply + 1, player_name(review.player), square_name(review.row, review.col), review.class.label(), note);
+    }
+    println!();
+    print!("{}", review);
+}
+
+// 評価値の推移のグラフと現在の黒の勝率を表示
+fn print_graph(game: &Game) {
+    let review = game.review();
+    match review.evaluation_series().last() {
+        Some(point) => {
+            println!("評価値の推移（X: 黒優勢 / O: 白優勢、縦軸は予想される最終石差）");
+            print!("{}", review.ascii_chart(6));
+            println!("現在の予想: 黒から見て {:+} 石、黒の勝率 {:.0}%", point.advantage, point.win_probability * 100.0);
+        }
+        None => println!("まだ手が打たれていません"),
+    }
+}
+
+// 強さの自動調整が有効なら終局した対局の結果を記録して保存する
+fn record_result(game: &Game, options: &mut Options) {
+    let human = options.human;
+    if let Some((adaptive, _)) = &mut options.adaptive {
+        adaptive.record_result(game, human);
+        options.save_adaptive();
+        println!("AI の難易度: {}", options.ai_description());
+    }
+}
+
+// 難易度や強さを直接指定したときは強さの自動調整を保存してから止める
+fn disable_adaptive(options: &mut Options) {
+    options.save_adaptive();
+    options.adaptive = None;
+}
+
+// 1行分のコマンドを実行。終了する場合は false を返す
+fn run_command(line: &str, game: &mut Game, options: &mut Options) -> bool {
+    let mut words = line.split_whitespace();
+    let Some(command) = words.next() else {
+        return true;
+    };
+
+    match command.to_ascii_lowercase().as_str() {
+        "quit" | "exit" | "q" => return false,
+        "help" | "?" => println!("{}", HELP),
+        "board" => print_status(game, options.human),
+        "moves" => {
+            let moves: Vec<String> = game.valid_moves().iter().map(|&(row, col)| square_name(row, col)).collect();
+            println!("有効な手: {}", moves.join(" "));
+        }
+        "stable" => {
+            for player in [1, 2] {
+                let squares: Vec<String> = game.stable_discs(player).iter().map(|&(row, col)| square_name(row, col)).collect();
+                println!("{} の確定石: {} 個 {}", player_name(player), squares.len(), squares.join(" "));
+            }
+        }
+        "hint" => match game.ai_move_with(options.hint_config()) {
+            Some((row, col)) => {
+                println!("おすすめの手: {}", square_name(row, col));
+                if let Some(explanation) = game.explain_move_with(row, col, options.language, options.hint_config().weights) {
+                    println!("  {}", explanation);
+                }
+            }
+            None => println!("有効な手がありません"),
+        },
+        "undo" => {
+            // AI の手も含めて、自分の直前の手を打つ前の局面に戻す
+            if game.history().iter().any(|&(_, _, player)| player == options.human) {
+                while let Some((_, _, player)) = game.undo() {
+                    if player == options.human {
+                        break;
+                    }
+                }
+                print_status(game, options.human);
+            } else {
+                println!("取り消せる手がありません");
+            }
+        }
+        "difficulty" => match words.next().map(str::parse::<AiDifficulty>) {
+            Some(Ok(difficulty)) => {
+                options.difficulty = difficulty;
+                options.strength = None;
+                disable_adaptive(options);
+                println!("AI の難易度: {}", options.ai_description());
+            }
+            Some(Err(message)) => println!("{}", message),
+            None => println!("AI の難易度: {}", options.ai_description()),
+        },
+        "strength" | "elo" => match words.next().map(parse_number) {
+            Some(Ok(value)) => {
+                let strength = if command.eq_ignore_ascii_case("elo") { Strength::from_elo(value) } else { Strength::new(value) };
+                options.strength = Some(strength);
+                disable_adaptive(options);
+                println!("AI の難易度: {}", options.ai_description());
+            }
+            Some(Err(message)) => println!("{}", message),
+            None => println!("AI の難易度: {}", options.ai_description()),
+        },
+        "personality" => match words.next().map(str::parse::<Personality>) {
+            Some(Ok(personality)) => {
+                options.personality = personality;
+                println!("AI の難易度: {}", options.ai_description());
+            }
+            Some(Err(message)) => println!("{}", message),
+            None => println!("AI の難易度: {}", options.ai_description()),
+        },
+        "transcript" => println!("{}", game.transcript()),
+        "perft" => match words.next().map(str::parse::<u32>) {
+            Some(Ok(depth)) => {
+                let started = std::time::Instant::now();
+                let mut total = 0;
+                for ((row, col), nodes) in perft::divide(game, depth) {
+                    println!("{}: {}", square_name(row, col), nodes);
+                    total += nodes;
+                }
+                println!("合計: {} ({:.2} 秒)", total, started.elapsed().as_secs_f64());
+            }
+            _ => println!("使い方: perft <深さ>"),
+        },
+        "eval" => {
+            let player = game.current_player();
+            println!("{} から見た評価値の内訳:", player_name(player));
+            println!("{}", game.evaluation_breakdown_with(player, options.hint_config().weights));
+        }
+        "review" => print_review(game),
+        "graph" => print_graph(game),
+        "save" => match words.next() {
+            Some(path) => match std::fs::write(path, format!("{}\n", game.transcript())) {
+                Ok(()) => println!("棋譜を {} に保存しました", path),
+                Err(error) => println!("保存できませんでした: {}", error),
+            },
+            None => println!("保存先のファイル名を指定してください"),
+        },
+        text => match parse_square(text) {
+            Some((row, col)) if game.current_player() == options.human => {
+                let before = game.clone();
+                match game.play(row, col) {
+                    Ok(()) => {
+                        if let Some((adaptive, _)) = &mut options.adaptive {
+                            adaptive.record_move(&before, row, col);
+                        }
+                        play_ai_turns(game, options);
+                        print_status(game, options.human);
+                        if game.is_game_over() {
+                            record_result(game, options);
+                        }
+                    }
+                    Err(message) => println!("{}", message),
+                }
+            }
+            Some(_) => println!("あなたの番ではありません"),
+            None => println!("不明なコマンドです: {} (help でコマンド一覧を表示)", text),
+        },
+    }
+    true
+}
+
+fn main() -> ExitCode {
+    let mut options = match parse_args() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut game = options.game.clone();
+
+    println!("あなたは {}、AI は {} です", player_name(options.human), options.ai_description());
+    play_ai_turns(&mut game, &options);
+    print_status(&game, options.human);
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        let _ = io::stdout().flush();
+        let Some(Ok(line)) = lines.next() else {
+            break;
+        };
+        if !run_command(&line, &mut game, &mut options) {
+            break;
+        }
+    }
+    options.save_adaptive();
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,177 @@
+// AI 同士の総当たり戦を行い、勝率・石差・Elo レーティングを表示するツール
+//
+// 使い方: tournament [--player 名前:設定]... [--openings 局面数] [--opening-plies 手数] [--verbose]
+//         tournament --calibrate [--levels 0,10,...,100] [--openings 局面数]
+// 設定は AiConfig の形式（例: "hard", "depth=6,time=200", "expert,mobility=20", "strength=60"）。
+// 名前: を省略した場合は設定の文字列をそのまま名前にする（--player hard は --player hard:hard と同じ）。
+// --player を省略した場合は4つの難易度で総当たり戦を行う。
+// --calibrate は指定した強さ同士で総当たり戦を行い、強さとレーティングの対応表を出力する。
+
+use std::process::ExitCode;
+
+use mini_wasm_othello::tournament::{self, EloEstimate, Entrant};
+use mini_wasm_othello::{AiConfig, Strength};
+
+const USAGE: &str = "使い方: tournament [--player 名前:設定]... [--openings 局面数] [--opening-plies 手数] [--verbose]
+       tournament --calibrate [--levels 0,10,...,100] [--openings 局面数]
+名前: を省略した場合は設定の文字列を名前にします（例: --player hard, --player strong:depth=6）";
+
+struct Options {
+    entrants: Vec<Entrant>,
+    openings: usize,
+    opening_plies: usize,
+    verbose: bool,
+    calibrate: bool,
+    levels: Vec<f64>,
+}
+
+// 名前:設定 を解釈する（名前を省略した場合は設定の文字列を名前にする）
+fn parse_entrant(text: &str) -> Result<Entrant, String> {
+    let (name, spec) = text.split_once(':').unwrap_or((text, text));
+    Ok(Entrant {
+        name: name.to_string(),
+        config: spec.parse::<AiConfig>()?,
+    })
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        entrants: Vec::new(),
+        openings: 8,
+        opening_plies: 6,
+        verbose: false,
+        calibrate: false,
+        levels: (0..=10).map(|level| level as f64 * 10.0).collect(),
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} の値がありません", arg));
+        match arg.as_str() {
+            "-p" | "--player" => options.entrants.push(parse_entrant(&value()?)?),
+            "-n" | "--openings" => options.openings = value()?.parse().map_err(|_| "開始局面の数が不正です".to_string())?,
+            "--opening-plies" => options.opening_plies = value()?.parse().map_err(|_| "開始局面の手数が不正です".to_string())?,
+            "-v" | "--verbose" => options.verbose = true,
+            "--calibrate" => options.calibrate = true,
+            "--levels" => {
+                options.levels = value()?
+                    .split(',')
+                    .map(|level| level.trim().parse::<f64>().map_err(|_| format!("不正な強さです: {}", level)))
+                    .collect::<Result<_, _>>()?
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            other => return Err(format!("不明な引数です: {}\n{}", other, USAGE)),
+        }
+    }
+
+    if options.calibrate {
+        options.entrants = options
+            .levels
+            .iter()
+            .map(|&level| Entrant {
+                name: format!("strength={}", level),
+                config: Strength::new(level).config(),
+            })
+            .collect();
+    } else if options.entrants.is_empty() {
+        for name in ["easy", "medium", "hard", "expert"] {
+            options.entrants.push(parse_entrant(name)?);
+        }
+    }
+    if options.entrants.len() < 2 {
+        return Err("参加者は2人以上必要です".to_string());
+    }
+    Ok(options)
+}
+
+fn format_elo(estimate: EloEstimate) -> String {
+    // -0 と表示されないよう 0.0 を足す
+    format!("{:+7.0} [{:+.0}, {:+.0}]", estimate.elo + 0.0, estimate.lower + 0.0, estimate.upper + 0.0)
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let openings = tournament::balanced_openings(options.opening_plies, options.openings);
+    eprintln!("開始局面: {} 個（{} 手目）", openings.len(), options.opening_plies);
+    if options.verbose {
+        for (index, opening) in openings.iter().enumerate() {
+            eprintln!("  #{} {}", index, opening.transcript());
+        }
+    }
+
+    let names: Vec<&str> = options.entrants.iter().map(|entrant| entrant.name.as_str()).collect();
+    let total = options.entrants.len() * (options.entrants.len() - 1) * openings.len();
+    let mut played = 0;
+    let records = tournament::round_robin(&options.entrants, &openings, |record| {
+        played += 1;
+        eprintln!(
+            "[{}/{}] {} (黒) {}-{} {} (白) 開始局面 #{}",
+            played, total, names[record.black], record.black_discs, record.white_discs, names[record.white], record.opening
+        );
+        if options.verbose {
+            eprintln!("  {}", record.transcript);
+        }
+    });
+
+    // 参加者ごとの成績（Elo は対戦相手全体の平均との差）
+    let width = names.iter().map(|name| name.chars().count()).max().unwrap_or(0).max(4);
+    println!();
+    println!("{:width$}  対局  勝  分  負  勝率    平均石差  Elo [95%信頼区間]", "名前", width = width);
+    for (name, standing) in names.iter().zip(tournament::standings(names.len(), &records)) {
+        println!(
+            "{:width$}  {:4} {:3} {:3} {:3}  {:5.1}%  {:+8.2}  {}",
+            name,
+            standing.games,
+            standing.wins,
+            standing.draws,
+            standing.losses,
+            standing.win_rate() * 100.0,
+            standing.average_disc_diff(),
+            format_elo(EloEstimate::from_scores(&standing.scores)),
+            width = width
+        );
+    }
+
+    // 直接対決（行の参加者から見た Elo 差）
+    println!();
+    println!("直接対決（行から見た Elo 差 [95%信頼区間]）");
+    for (first, name) in names.iter().enumerate() {
+        for (second, opponent) in names.iter().enumerate() {
+            if first == second {
+                continue;
+            }
+            let scores = tournament::head_to_head(first, second, &records);
+            let wins: f64 = scores.iter().sum();
+            println!(
+                "{:width$} vs {:width$}  {:4.1}/{:<3}  {}",
+                name,
+                opponent,
+                wins,
+                scores.len(),
+                format_elo(EloEstimate::from_scores(&scores)),
+                width = width
+            );
+        }
+    }
+
+    if options.calibrate {
+        // 強さとレーティングの対応表（Strength の CALIBRATION にそのまま貼り付けられる形式）
+        println!();
+        println!("強さとレーティングの対応（最初の強さを 0 とした最尤推定値）");
+        for (level, rating) in options.levels.iter().zip(tournament::fit_ratings(names.len(), &records)) {
+            println!("    ({:.1}, {:.0}),", level, rating + 0.0);
+        }
+    }
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,193 @@
+// ビットボードによる盤面表現
+//
+// 手番側と相手側の石をそれぞれ 64 ビット整数で表す（ビット番号は 行 × 8 + 列）。
+// 着手可能な手と裏返る石をシフト演算でまとめて求められるため、
+// 終盤の完全読みのように大量の局面を調べる探索で使う。
+
+use crate::board::BoardState;
+
+// 8方向のシフト量と、シフト後に盤外から回り込んだビットを消すマスク
+const DIRECTIONS: [(i32, u64); 8] = [
+    (1, 0xfefe_fefe_fefe_fefe),  // 右
+    (-1, 0x7f7f_7f7f_7f7f_7f7f), // 左
+    (8, 0xffff_ffff_ffff_ffff),  // 下
+    (-8, 0xffff_ffff_ffff_ffff), // 上
+    (9, 0xfefe_fefe_fefe_fefe),  // 右下
+    (7, 0x7f7f_7f7f_7f7f_7f7f),  // 左下
+    (-7, 0xfefe_fefe_fefe_fefe), // 右上
+    (-9, 0x7f7f_7f7f_7f7f_7f7f), // 左上
+];
+
+pub(crate) fn shift(bits: u64, amount: i32, mask: u64) -> u64 {
+    if amount > 0 {
+        (bits << amount) & mask
+    } else {
+        (bits >> -amount) & mask
+    }
+}
+
+// 手番側から見た盤面
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Bitboard {
+    pub(crate) player: u64,   // 手番側の石
+    pub(crate) opponent: u64, // 相手側の石
+}
+
+impl Bitboard {
+    // 配列の盤面と手番から作成
+    pub(crate) fn from_board(board: &BoardState, player: i8) -> Self {
+        let mut bitboard = Bitboard { player: 0, opponent: 0 };
+        for row in 0..8 {
+            for col in 0..8 {
+                let bit = 1u64 << (row * 8 + col);
+                match board.board[row][col] {
+                    0 => {}
+                    piece if piece == player => bitboard.player |= bit,
+                    _ => bitboard.opponent |= bit,
+                }
+            }
+        }
+        bitboard
+    }
+
+    // 手番側が打てるマスの集合
+    pub(crate) fn moves(self) -> u64 {
+        // 横・斜めの向きは、左右の端の列の相手の石を除いて次の行への回り込みを防ぐ
+        let inner = self.opponent & 0x7e7e_7e7e_7e7e_7e7e;
+        let moves = line_moves(self.player, inner, 1)
+            | line_moves(self.player, self.opponent, 8)
+            | line_moves(self.player, inner, 7)
+            | line_moves(self.player, inner, 9);
+        moves & !(self.player | self.opponent)
+    }
+
+    // square（ビット番号）に打ったときに裏返る石の集合
+    pub(crate) fn flips(self, square: u32) -> u64 {
+        let origin = 1u64 << square;
+        let mut flips = 0;
+        for (amount, mask) in DIRECTIONS {
+            let mut line = 0;
+            let mut bit = shift(origin, amount, mask);
+            while bit & self.opponent != 0 {
+                line |= bit;
+                bit = shift(bit, amount, mask);
+            }
+            if bit & self.player != 0 {
+                flips |= line;
+            }
+        }
+        flips
+    }
+
+    // square に打った後の局面（手番が相手に移る）
+    pub(crate) fn play(self, square: u32) -> Self {
+        self.play_flips(square, self.flips(square))
+    }
+
+    // square に打って flips（flips(square) で求めた石）を裏返した後の局面
+    pub(crate) fn play_flips(self, square: u32, flips: u64) -> Self {
+        Bitboard {
+            player: self.opponent & !flips,
+            opponent: self.player | flips | (1u64 << square),
+        }
+    }
+
+    // パスした後の局面
+    pub(crate) fn pass(self) -> Self {
+        Bitboard {
+            player: self.opponent,
+            opponent: self.player,
+        }
+    }
+
+    // 空きマスの数
+    pub(crate) fn empties(self) -> u32 {
+        (!(self.player | self.opponent)).count_ones()
+    }
+
+    // 終局時の手番側から見た石差（空きマスは勝った側に数える）
+    pub(crate) fn final_score(self) -> i32 {
+        let diff = self.player.count_ones() as i32 - self.opponent.count_ones() as i32;
+        let empties = self.empties() as i32;
+        match diff.signum() {
+            1 => diff + empties,
+            -1 => diff - empties,
+            _ => 0,
+        }
+    }
+}
+
+// シフト量 amount の正負の向きそれぞれについて、手番側の石から相手の石が続く範囲の先のマス
+#[inline(always)]
+fn line_moves(player: u64, opponent: u64, amount: u32) -> u64 {
+    let mut forward = opponent & (player << amount);
+    let mut backward = opponent & (player >> amount);
+    for _ in 0..5 {
+        forward |= opponent & (forward << amount);
+        backward |= opponent & (backward >> amount);
+    }
+    (forward << amount) | (backward >> amount)
+}
+
+// bits のいずれかのマスに縦・横・斜めで隣接するマスの集合
+pub(crate) fn adjacent(bits: u64) -> u64 {
+    DIRECTIONS.iter().fold(0, |adjacent, &(amount, mask)| adjacent | shift(bits, amount, mask))
+}
+
+// ビットの集合を順に取り出すイテレータ（ビット番号の小さい順）
+pub(crate) fn squares(mut bits: u64) -> impl Iterator<Item = u32> {
+    std::iter::from_fn(move || {
+        if bits == 0 {
+            return None;
+        }
+        let square = bits.trailing_zeros();
+        bits &= bits - 1;
+        Some(square)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::testing::random_positions;
+
+    fn to_bits(squares: &[(usize, usize)]) -> u64 {
+        squares.iter().fold(0, |bits, &(row, col)| bits | 1u64 << (row * 8 + col))
+    }
+
+    #[test]
+    fn moves_match_array_generator() {
+        for (board, player) in random_positions(300, 1) {
+            for side in [player, 3 - player] {
+                let bitboard = Bitboard::from_board(&board, side);
+                assert_eq!(bitboard.moves(), to_bits(&board.get_valid_moves_for_board(side)), "{}", board.to_position_string(side));
+            }
+        }
+    }
+
+    #[test]
+    fn flips_and_play_match_array_board() {
+        for (board, player) in random_positions(300, 2) {
+            let bitboard = Bitboard::from_board(&board, player);
+            for (row, col) in board.get_valid_moves_for_board(player) {
+                let square = (row * 8 + col) as u32;
+                assert_eq!(bitboard.flips(square), to_bits(&board.get_flips(row, col, player)));
+
+                let mut after = board.clone();
+                after.make_move_on_board(row, col, player);
+                assert_eq!(bitboard.play(square), Bitboard::from_board(&after, 3 - player));
+            }
+        }
+    }
+
+    #[test]
+    fn counts_are_consistent() {
+        for (board, player) in random_positions(300, 3) {
+            let bitboard = Bitboard::from_board(&board, player);
+            assert_eq!(bitboard.player & bitboard.opponent, 0);
+            assert_eq!(bitboard.moves() & (bitboard.player | bitboard.opponent), 0);
+            assert_eq!(bitboard.empties() as usize, board.board.iter().flatten().filter(|&&piece| piece == 0).count());
+            assert_eq!(bitboard.pass().pass(), bitboard);
+        }
+    }
+}
@@ -0,0 +1,303 @@
+// 盤面の状態とルール（着手・合法手の判定）
+//
+// ゲーム本体（OthelloGame）に依存しないため、Canvas のない Web Worker や
+// ネイティブ環境の探索からも利用できる。
+
+// ゾブリストハッシュ用の乱数（コンパイル時に splitmix64 で生成）
+const fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+const ZOBRIST: [[u64; 64]; 2] = {
+    let mut table = [[0; 64]; 2];
+    let mut i = 0;
+    while i < 128 {
+        table[i / 64][i % 64] = splitmix64(i as u64 + 1);
+        i += 1;
+    }
+    table
+};
+
+const ZOBRIST_WHITE_TO_MOVE: u64 = splitmix64(1000);
+
+// ボード状態を表現する構造体（AI計算用）
+#[derive(Clone)]
+pub(crate) struct BoardState {
+    pub(crate) board: [[i8; 8]; 8], // 0: 空, 1: 黒, 2: 白
+}
+
+impl BoardState {
+    // ボード上で手を実行
+    pub(crate) fn make_move_on_board(&mut self, row: usize, col: usize, player: i8) {
+        self.board[row][col] = player;
+
+        let directions = [
+            (-1, -1), (-1, 0), (-1, 1),
+            (0, -1),           (0, 1),
+            (1, -1),  (1, 0),  (1, 1),
+        ];
+
+        for (dr, dc) in directions.iter() {
+            if self.can_flip_in_direction_board(row, col, *dr, *dc, player) {
+                self.flip_in_direction_board(row, col, *dr, *dc, player);
+            }
+        }
+    }
+
+    // 指定したマスに置いた場合にひっくり返る石の一覧を取得（盤面は変更しない）
+    pub(crate) fn get_flips(&self, row: usize, col: usize, player: i8) -> Vec<(usize, usize)> {
+        let mut flips = Vec::new();
+        if self.board[row][col] != 0 {
+            return flips;
+        }
+
+        let directions = [
+            (-1, -1), (-1, 0), (-1, 1),
+            (0, -1),           (0, 1),
+            (1, -1),  (1, 0),  (1, 1),
+        ];
+
+        for (dr, dc) in directions.iter() {
+            if self.can_flip_in_direction_board(row, col, *dr, *dc, player) {
+                let mut r = row as i32 + dr;
+                let mut c = col as i32 + dc;
+                while self.board[r as usize][c as usize] != player {
+                    flips.push((r as usize, c as usize));
+                    r += dr;
+                    c += dc;
+                }
+            }
+        }
+
+        flips
+    }
+
+    // ボード上で指定した方向に石をひっくり返せるかチェック
+    fn can_flip_in_direction_board(&self, row: usize, col: usize, dr: i32, dc: i32, player: i8) -> bool {
+        let mut r = row as i32 + dr;
+        let mut c = col as i32 + dc;
+        let mut found_opponent = false;
+
+        while (0..8).contains(&r) && (0..8).contains(&c) {
+            let current_piece = self.board[r as usize][c as usize];
+
+            if current_piece == 0 {
+                return false;
+            } else if current_piece == player {
+                return found_opponent;
+            } else {
+                found_opponent = true;
+            }
+
+            r += dr;
+            c += dc;
+        }
+
+        false
+    }
+
+    // ボード上で指定した方向の石をひっくり返す
+    fn flip_in_direction_board(&mut self, row: usize, col: usize, dr: i32, dc: i32, player: i8) {
+        let mut r = row as i32 + dr;
+        let mut c = col as i32 + dc;
+
+        while (0..8).contains(&r) && (0..8).contains(&c) {
+            let current_piece = self.board[r as usize][c as usize];
+
+            if current_piece == player {
+                break;
+            } else {
+                self.board[r as usize][c as usize] = player;
+            }
+
+            r += dr;
+            c += dc;
+        }
+    }
+
+    // 特定方向でひっくり返せる石の数を数える
+    pub(crate) fn count_flips_in_direction(&self, row: usize, col: usize, dr: i32, dc: i32, player: i8) -> i32 {
+        let mut r = row as i32 + dr;
+        let mut c = col as i32 + dc;
+        let mut count = 0;
+
+        while (0..8).contains(&r) && (0..8).contains(&c) {
+            let current_piece = self.board[r as usize][c as usize];
+
+            if current_piece == 0 {
+                return 0; // 空のマスに到達
+            } else if current_piece == player {
+                return count; // 自分の石に到達
+            } else {
+                count += 1; // 相手の石をカウント
+            }
+
+            r += dr;
+            c += dc;
+        }
+
+        0 // 境界に到達
+    }
+
+    // ボード状態用の有効な手を取得
+    pub(crate) fn get_valid_moves_for_board(&self, player: i8) -> Vec<(usize, usize)> {
+        let mut moves = Vec::new();
+        for row in 0..8 {
+            for col in 0..8 {
+                if self.is_valid_move_for_board(row, col, player) {
+                    moves.push((row, col));
+                }
+            }
+        }
+        moves
+    }
+
+    // ボード状態での有効な手チェック
+    pub(crate) fn is_valid_move_for_board(&self, row: usize, col: usize, player: i8) -> bool {
+        if self.board[row][col] != 0 {
+            return false;
+        }
+
+        let directions = [
+            (-1, -1), (-1, 0), (-1, 1),
+            (0, -1),           (0, 1),
+            (1, -1),  (1, 0),  (1, 1),
+        ];
+
+        for (dr, dc) in directions.iter() {
+            if self.can_flip_in_direction_board(row, col, *dr, *dc, player) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    // 終端状態かどうかをチェック
+    pub(crate) fn is_terminal_state(&self) -> bool {
+        // 盤面が満杯または両プレイヤーに有効な手がない
+        let mut empty_count = 0;
+        for row in 0..8 {
+            for col in 0..8 {
+                if self.board[row][col] == 0 {
+                    empty_count += 1;
+                }
+            }
+        }
+
+        if empty_count == 0 {
+            return true;
+        }
+
+        // 両プレイヤーに有効な手があるかチェック
+        let has_moves_1 = !self.get_valid_moves_for_board(1).is_empty();
+        let has_moves_2 = !self.get_valid_moves_for_board(2).is_empty();
+
+        !has_moves_1 && !has_moves_2
+    }
+
+    // 局面と手番のハッシュ値（置換表用）
+    pub(crate) fn hash(&self, player: i8) -> u64 {
+        let mut hash = if player == 2 { ZOBRIST_WHITE_TO_MOVE } else { 0 };
+        for row in 0..8 {
+            for col in 0..8 {
+                match self.board[row][col] {
+                    1 => hash ^= ZOBRIST[0][row * 8 + col],
+                    2 => hash ^= ZOBRIST[1][row * 8 + col],
+                    _ => {}
+                }
+            }
+        }
+        hash
+    }
+
+    // 局面を文字列に変換（64マス分の X: 黒, O: 白, -: 空 と、空白に続く手番 X / O）
+    pub(crate) fn to_position_string(&self, player: i8) -> String {
+        let mut position = String::with_capacity(66);
+        for row in 0..8 {
+            for col in 0..8 {
+                position.push(match self.board[row][col] {
+                    1 => 'X',
+                    2 => 'O',
+                    _ => '-',
+                });
+            }
+        }
+        position.push(' ');
+        position.push(if player == 1 { 'X' } else { 'O' });
+        position
+    }
+
+    // 文字列から局面と手番を復元（to_position_string の逆変換）
+    pub(crate) fn from_position_string(position: &str) -> Result<(BoardState, i8), String> {
+        let chars: Vec<char> = position.chars().filter(|c| !c.is_whitespace()).collect();
+        if chars.len() != 65 {
+            return Err(format!("局面の文字数が不正です: {}", chars.len()));
+        }
+
+        let mut state = BoardState { board: [[0; 8]; 8] };
+        for (index, &c) in chars[..64].iter().enumerate() {
+            state.board[index / 8][index % 8] = match c {
+                'X' | 'x' | '*' => 1,
+                'O' | 'o' => 2,
+                '-' | '.' => 0,
+                _ => return Err(format!("不正な文字です: {}", c)),
+            };
+        }
+
+        let player = match chars[64] {
+            'X' | 'x' | '*' => 1,
+            'O' | 'o' => 2,
+            c => return Err(format!("不正な手番です: {}", c)),
+        };
+
+        Ok((state, player))
+    }
+}
+
+// テスト用の局面
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::BoardState;
+    use crate::rng::Rng;
+
+    // 初期局面
+    pub(crate) fn start() -> BoardState {
+        let mut board = BoardState { board: [[0; 8]; 8] };
+        board.board[3][3] = 2;
+        board.board[3][4] = 1;
+        board.board[4][3] = 1;
+        board.board[4][4] = 2;
+        board
+    }
+
+    // 初期局面から乱数で手を進めた、手番側に有効な手がある局面（パスの後の局面も含む）
+    pub(crate) fn random_positions(count: usize, seed: u64) -> Vec<(BoardState, i8)> {
+        let mut rng = Rng::new(seed);
+        let mut positions = Vec::new();
+        while positions.len() < count {
+            let mut board = start();
+            let mut player = 1;
+            let plies = 1 + rng.below(60);
+            for _ in 0..plies {
+                let moves = board.get_valid_moves_for_board(player);
+                if moves.is_empty() {
+                    if board.get_valid_moves_for_board(3 - player).is_empty() {
+                        break;
+                    }
+                } else {
+                    let (row, col) = moves[rng.below(moves.len())];
+                    board.make_move_on_board(row, col, player);
+                }
+                player = 3 - player;
+            }
+            if !board.get_valid_moves_for_board(player).is_empty() {
+                positions.push((board, player));
+            }
+        }
+        positions
+    }
+}
@@ -0,0 +1,138 @@
+// 定石（序盤の手順）
+//
+// 代表的な定石の手順を局面ごとの候補手の表に展開し、定石の局面では
+// 候補手から無作為に選べるようにする（毎局同じ序盤にならないようにするため）。
+// 手順は黒の初手 f5 の形だけを持ち、対称な形は表を作るときに追加する。
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::board::BoardState;
+
+// f5 から始まる定石の手順
+const LINES: [&str; 10] = [
+    "f5d6c3d3c4f4c5b3c2",
+    "f5d6c3d3c4f4f6f3e6e7",
+    "f5d6c3d3c4f4f6g5",
+    "f5d6c3d3c4b3",
+    "f5d6c5f4e3c6d3f6e6d7",
+    "f5d6c4",
+    "f5f6e6f4e3c5c4",
+    "f5f6e6f4e3d6",
+    "f5f6e6f4g5",
+    "f5f4e3f6d3",
+];
+
+// 局面のハッシュ値（BoardState::hash）から定石の候補手への表
+fn table() -> &'static HashMap<u64, Vec<(usize, usize)>> {
+    static TABLE: OnceLock<HashMap<u64, Vec<(usize, usize)>>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table: HashMap<u64, Vec<(usize, usize)>> = HashMap::new();
+        for line in LINES {
+            let moves = parse_line(line);
+
+            // 8通りの対称変換のうち、初期配置を変えないもの（初手が有効になるもの）だけが残る
+            for symmetry in 0..8 {
+                let mut board = initial_board();
+                let mut player = 1;
+                let mut entries = Vec::new();
+                for &(row, col) in &moves {
+                    let (row, col) = transform(row, col, symmetry);
+                    if !board.is_valid_move_for_board(row, col, player) {
+                        entries.clear();
+                        break;
+                    }
+                    entries.push((board.hash(player), (row, col)));
+                    board.make_move_on_board(row, col, player);
+                    player = 3 - player;
+                }
+
+                for (hash, square) in entries {
+                    let candidates = table.entry(hash).or_default();
+                    if !candidates.contains(&square) {
+                        candidates.push(square);
+                    }
+                }
+            }
+        }
+        table
+    })
+}
+
+// 手順の文字列を (行, 列) の並びに変換
+fn parse_line(line: &str) -> Vec<(usize, usize)> {
+    line.as_bytes()
+        .chunks(2)
+        .map(|square| ((square[1] - b'1') as usize, (square[0] - b'a') as usize))
+        .collect()
+}
+
+fn initial_board() -> BoardState {
+    let mut board = BoardState { board: [[0; 8]; 8] };
+    board.board[3][3] = 2;
+    board.board[3][4] = 1;
+    board.board[4][3] = 1;
+    board.board[4][4] = 2;
+    board
+}
+
+// 対称変換（左右反転・上下反転・対角線での反転の組み合わせ）
+fn transform(mut row: usize, mut col: usize, symmetry: u8) -> (usize, usize) {
+    if symmetry & 1 != 0 {
+        col = 7 - col;
+    }
+    if symmetry & 2 != 0 {
+        row = 7 - row;
+    }
+    if symmetry & 4 != 0 {
+        std::mem::swap(&mut row, &mut col);
+    }
+    (row, col)
+}
+
+// 局面が定石に含まれる場合はその候補手を返す（含まれない場合は空）
+pub(crate) fn book_moves(board: &BoardState, player: i8) -> &'static [(usize, usize)] {
+    table().get(&board.hash(player)).map_or(&[], Vec::as_slice)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_line_is_legal() {
+        for line in LINES {
+            for symmetry in 0..8 {
+                let moves = parse_line(line);
+                let mut board = initial_board();
+                let mut player = 1;
+                // 初手が無効な対称変換は表に入らないので、初手が有効なら最後まで有効でなければならない
+                let (row, col) = transform(moves[0].0, moves[0].1, symmetry);
+                if !board.is_valid_move_for_board(row, col, player) {
+                    continue;
+                }
+                for (ply, &(row, col)) in moves.iter().enumerate() {
+                    let (row, col) = transform(row, col, symmetry);
+                    assert!(
+                        board.is_valid_move_for_board(row, col, player),
+                        "{} の {} 手目（対称変換 {}）が無効です",
+                        line,
+                        ply + 1,
+                        symmetry
+                    );
+                    assert!(book_moves(&board, player).contains(&(row, col)));
+                    board.make_move_on_board(row, col, player);
+                    player = 3 - player;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn start_position_is_in_the_book() {
+        let mut moves = book_moves(&initial_board(), 1).to_vec();
+        moves.sort();
+        // f5 とその対称な d3 / c4 / e6
+        assert_eq!(moves, vec![(2, 3), (3, 2), (4, 5), (5, 4)]);
+    }
+}
@@ -0,0 +1,426 @@
+// 局面図（SVG / PNG）の出力
+//
+// 盤面と棋譜（着手履歴）から記事などに貼り付けられる局面図を生成する。
+// SVG はブラウザでもネイティブでも利用でき、PNG はネイティブ環境でのみ利用できる。
+
+use std::fmt::Write;
+
+// 列の座標ラベル
+const COLUMN_LABELS: [char; 8] = ['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h'];
+
+// 着手の記録（行, 列, 手番）
+pub type MoveRecord = (usize, usize, i8);
+
+// 局面図の出力オプション
+#[derive(Debug, Clone)]
+pub struct DiagramOptions {
+    pub cell_size: u32,                 // 1マスの大きさ（px）
+    pub show_coordinates: bool,         // 座標（a-h, 1-8）を表示するか
+    pub show_move_numbers: bool,        // 石に手数を表示するか
+    pub highlights: Vec<(usize, usize)>, // 強調表示するマス
+}
+
+impl Default for DiagramOptions {
+    fn default() -> Self {
+        DiagramOptions {
+            cell_size: 48,
+            show_coordinates: true,
+            show_move_numbers: false,
+            highlights: Vec::new(),
+        }
+    }
+}
+
+impl DiagramOptions {
+    // 座標表示用の余白
+    fn margin(&self) -> f64 {
+        if self.show_coordinates {
+            self.cell_size as f64 / 2.0
+        } else {
+            0.0
+        }
+    }
+
+    // 画像全体の大きさ
+    fn image_size(&self) -> f64 {
+        self.cell_size as f64 * 8.0 + self.margin() * 2.0
+    }
+}
+
+// 各マスに最後に置かれた石の手数を求める（裏返しでは変わらない）
+fn move_numbers(moves: &[MoveRecord]) -> [[usize; 8]; 8] {
+    let mut numbers = [[0; 8]; 8];
+    for (index, &(row, col, _)) in moves.iter().enumerate() {
+        if row < 8 && col < 8 {
+            numbers[row][col] = index + 1;
+        }
+    }
+    numbers
+}
+
+// 局面図をSVG文字列として出力
+pub fn render_svg(board: &[[i8; 8]; 8], moves: &[MoveRecord], options: &DiagramOptions) -> String {
+    let cell_size = options.cell_size as f64;
+    let margin = options.margin();
+    let image_size = options.image_size();
+    let board_size = cell_size * 8.0;
+    let radius = cell_size * 0.4;
+    let font_size = cell_size * 0.35;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{0}" viewBox="0 0 {0} {0}">"#,
+        image_size
+    );
+    let _ = writeln!(svg, r##"<rect x="0" y="0" width="{0}" height="{0}" fill="#FFF"/>"##, image_size);
+    let _ = writeln!(
+        svg,
+        r##"<rect x="{0}" y="{0}" width="{1}" height="{1}" fill="#228B22"/>"##,
+        margin, board_size
+    );
+
+    // 強調表示するマス
+    for &(row, col) in &options.highlights {
+        if row < 8 && col < 8 {
+            let _ = writeln!(
+                svg,
+                r##"<rect x="{}" y="{}" width="{2}" height="{2}" fill="#FFD700" fill-opacity="0.6"/>"##,
+                margin + col as f64 * cell_size,
+                margin + row as f64 * cell_size,
+                cell_size
+            );
+        }
+    }
+
+    // グリッド
+    for i in 0..=8 {
+        let pos = margin + i as f64 * cell_size;
+        let _ = writeln!(
+            svg,
+            r##"<line x1="{0}" y1="{1}" x2="{0}" y2="{2}" stroke="#000" stroke-width="2"/>"##,
+            pos,
+            margin,
+            margin + board_size
+        );
+        let _ = writeln!(
+            svg,
+            r##"<line x1="{1}" y1="{0}" x2="{2}" y2="{0}" stroke="#000" stroke-width="2"/>"##,
+            pos,
+            margin,
+            margin + board_size
+        );
+    }
+
+    // 座標
+    if options.show_coordinates {
+        for (i, label) in COLUMN_LABELS.iter().enumerate() {
+            let center = margin + i as f64 * cell_size + cell_size / 2.0;
+            let _ = writeln!(
+                svg,
+                r##"<text x="{}" y="{}" font-family="sans-serif" font-size="{}" text-anchor="middle" dominant-baseline="central" fill="#000">{}</text>"##,
+                center,
+                margin / 2.0,
+                font_size,
+                label
+            );
+            let _ = writeln!(
+                svg,
+                r##"<text x="{}" y="{}" font-family="sans-serif" font-size="{}" text-anchor="middle" dominant-baseline="central" fill="#000">{}</text>"##,
+                margin / 2.0,
+                center,
+                font_size,
+                i + 1
+            );
+        }
+    }
+
+    // 石と手数
+    let numbers = move_numbers(moves);
+    for row in 0..8 {
+        for col in 0..8 {
+            let piece = board[row][col];
+            if piece == 0 {
+                continue;
+            }
+
+            let x = margin + col as f64 * cell_size + cell_size / 2.0;
+            let y = margin + row as f64 * cell_size + cell_size / 2.0;
+            let (fill, text_fill) = if piece == 1 { ("#000", "#FFF") } else { ("#FFF", "#000") };
+            let _ = writeln!(
+                svg,
+                r##"<circle cx="{}" cy="{}" r="{}" fill="{}" stroke="#000" stroke-width="2"/>"##,
+                x, y, radius, fill
+            );
+
+            if options.show_move_numbers && numbers[row][col] > 0 {
+                let _ = writeln!(
+                    svg,
+                    r##"<text x="{}" y="{}" font-family="sans-serif" font-size="{}" text-anchor="middle" dominant-baseline="central" fill="{}">{}</text>"##,
+                    x, y, font_size, text_fill, numbers[row][col]
+                );
+            }
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// 局面図をPNGバイト列として出力（ネイティブ環境専用）
+#[cfg(not(target_arch = "wasm32"))]
+pub fn render_png(board: &[[i8; 8]; 8], moves: &[MoveRecord], options: &DiagramOptions) -> Result<Vec<u8>, String> {
+    use tiny_skia::{Color, FillRule, Paint, PathBuilder, Pixmap, Rect, Stroke, Transform};
+
+    let cell_size = options.cell_size as f32;
+    let margin = options.margin() as f32;
+    let image_size = options.image_size().ceil() as u32;
+    let board_size = cell_size * 8.0;
+    let radius = cell_size * 0.4;
+    let font_size = cell_size * 0.35;
+
+    let mut pixmap = Pixmap::new(image_size, image_size).ok_or("画像サイズが不正です")?;
+    pixmap.fill(Color::WHITE);
+
+    let mut paint = Paint::default();
+
+    // ボードの背景
+    paint.set_color_rgba8(0x22, 0x8B, 0x22, 0xFF);
+    if let Some(rect) = Rect::from_xywh(margin, margin, board_size, board_size) {
+        pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+    }
+
+    // 強調表示するマス
+    paint.set_color_rgba8(0xFF, 0xD7, 0x00, 0x99);
+    for &(row, col) in &options.highlights {
+        if row < 8 && col < 8 {
+            let x = margin + col as f32 * cell_size;
+            let y = margin + row as f32 * cell_size;
+            if let Some(rect) = Rect::from_xywh(x, y, cell_size, cell_size) {
+                pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+            }
+        }
+    }
+
+    // グリッド
+    let stroke = Stroke { width: 2.0, ..Stroke::default() };
+    paint.set_color_rgba8(0x00, 0x00, 0x00, 0xFF);
+    let mut grid = PathBuilder::new();
+    for i in 0..=8 {
+        let pos = margin + i as f32 * cell_size;
+        grid.move_to(pos, margin);
+        grid.line_to(pos, margin + board_size);
+        grid.move_to(margin, pos);
+        grid.line_to(margin + board_size, pos);
+    }
+    if let Some(path) = grid.finish() {
+        pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    }
+
+    // 座標
+    if options.show_coordinates {
+        for (i, label) in COLUMN_LABELS.iter().enumerate() {
+            let center = margin + i as f32 * cell_size + cell_size / 2.0;
+            draw_text(&mut pixmap, &label.to_string(), center, margin / 2.0, font_size, [0x00, 0x00, 0x00]);
+            draw_text(&mut pixmap, &(i + 1).to_string(), margin / 2.0, center, font_size, [0x00, 0x00, 0x00]);
+        }
+    }
+
+    // 石と手数
+    let numbers = move_numbers(moves);
+    for row in 0..8 {
+        for col in 0..8 {
+            let piece = board[row][col];
+            if piece == 0 {
+                continue;
+            }
+
+            let x = margin + col as f32 * cell_size + cell_size / 2.0;
+            let y = margin + row as f32 * cell_size + cell_size / 2.0;
+            let Some(circle) = PathBuilder::from_circle(x, y, radius) else {
+                continue;
+            };
+
+            let (fill, text_fill) = if piece == 1 {
+                ([0x00, 0x00, 0x00], [0xFF, 0xFF, 0xFF])
+            } else {
+                ([0xFF, 0xFF, 0xFF], [0x00, 0x00, 0x00])
+            };
+            paint.set_color_rgba8(fill[0], fill[1], fill[2], 0xFF);
+            pixmap.fill_path(&circle, &paint, FillRule::Winding, Transform::identity(), None);
+            paint.set_color_rgba8(0x00, 0x00, 0x00, 0xFF);
+            pixmap.stroke_path(&circle, &paint, &stroke, Transform::identity(), None);
+
+            if options.show_move_numbers && numbers[row][col] > 0 {
+                draw_text(&mut pixmap, &numbers[row][col].to_string(), x, y, font_size, text_fill);
+            }
+        }
+    }
+
+    pixmap.encode_png().map_err(|e| e.to_string())
+}
+
+// 5x7 のビットマップフォント（数字と a-h のみ）
+#[cfg(not(target_arch = "wasm32"))]
+fn glyph(c: char) -> Option<[u8; 7]> {
+    let rows = match c {
+        '0' => [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110],
+        '1' => [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        '2' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+        '3' => [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+        '4' => [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+        '5' => [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+        '6' => [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+        '7' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+        '8' => [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+        '9' => [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+        'a' => [0b00000, 0b00000, 0b01110, 0b00001, 0b01111, 0b10001, 0b01111],
+        'b' => [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b11110],
+        'c' => [0b00000, 0b00000, 0b01110, 0b10000, 0b10000, 0b10001, 0b01110],
+        'd' => [0b00001, 0b00001, 0b01101, 0b10011, 0b10001, 0b10001, 0b01111],
+        'e' => [0b00000, 0b00000, 0b01110, 0b10001, 0b11111, 0b10000, 0b01110],
+        'f' => [0b00110, 0b01001, 0b01000, 0b11100, 0b01000, 0b01000, 0b01000],
+        'g' => [0b00000, 0b01111, 0b10001, 0b10001, 0b01111, 0b00001, 0b01110],
+        'h' => [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001],
+        _ => return None,
+    };
+    Some(rows)
+}
+
+// 指定した中心位置に文字列を描画（ビットマップフォントを拡大して描く）
+#[cfg(not(target_arch = "wasm32"))]
+fn draw_text(pixmap: &mut tiny_skia::Pixmap, text: &str, center_x: f32, center_y: f32, font_size: f32, color: [u8; 3]) {
+    use tiny_skia::{Paint, Rect, Transform};
+
+    let scale = (font_size / 7.0).max(1.0);
+    let glyph_width = 6.0 * scale; // 5px + 字間 1px
+    let text_width = glyph_width * text.chars().count() as f32 - scale;
+    let left = center_x - text_width / 2.0;
+    let top = center_y - 3.5 * scale;
+
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(color[0], color[1], color[2], 0xFF);
+
+    for (index, c) in text.chars().enumerate() {
+        let Some(rows) = glyph(c) else {
+            continue;
+        };
+        for (y, bits) in rows.iter().enumerate() {
+            for x in 0..5 {
+                if bits & (0b10000 >> x) != 0 {
+                    let px = left + index as f32 * glyph_width + x as f32 * scale;
+                    let py = top + y as f32 * scale;
+                    if let Some(rect) = Rect::from_xywh(px, py, scale, scale) {
+                        pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 初期局面から f5 に打った局面
+    fn after_f5() -> ([[i8; 8]; 8], Vec<MoveRecord>) {
+        let mut board = [[0; 8]; 8];
+        board[3][3] = 2;
+        board[3][4] = 1;
+        board[4][3] = 1;
+        board[4][4] = 1;
+        board[4][5] = 1;
+        (board, vec![(4, 5, 1)])
+    }
+
+    #[test]
+    fn svg_snapshot() {
+        let (board, moves) = after_f5();
+        let options = DiagramOptions {
+            cell_size: 10,
+            show_coordinates: false,
+            show_move_numbers: true,
+            highlights: vec![(2, 3)],
+        };
+        assert_eq!(render_svg(&board, &moves, &options), SVG_AFTER_F5);
+    }
+
+    #[test]
+    fn svg_coordinates_widen_the_image() {
+        let (board, moves) = after_f5();
+        let options = DiagramOptions {
+            cell_size: 10,
+            ..DiagramOptions::default()
+        };
+        let svg = render_svg(&board, &moves, &options);
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="90" height="90""#));
+        for label in ["a", "h", "1", "8"] {
+            assert!(svg.contains(&format!(">{}</text>", label)), "{} がありません", label);
+        }
+        // 手数は表示しない
+        assert_eq!(svg.matches("</text>").count(), 16);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn png_size_and_pixels() {
+        let (board, moves) = after_f5();
+        let options = DiagramOptions {
+            highlights: vec![(2, 3)],
+            ..DiagramOptions::default()
+        };
+        let png = render_png(&board, &moves, &options).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+        let pixmap = tiny_skia::Pixmap::decode_png(&png).unwrap();
+        // 1マス 48px、座標の余白 24px
+        assert_eq!((pixmap.width(), pixmap.height()), (432, 432));
+
+        // マスの中心の色
+        let pixel = |row: u32, col: u32| {
+            let color = pixmap.pixel(24 + col * 48 + 24, 24 + row * 48 + 24).unwrap();
+            (color.red(), color.green(), color.blue())
+        };
+        assert_eq!(pixel(3, 3), (0xFF, 0xFF, 0xFF)); // 白石
+        assert_eq!(pixel(4, 4), (0x00, 0x00, 0x00)); // 黒石
+        assert_eq!(pixel(0, 0), (0x22, 0x8B, 0x22)); // 空きマス
+        let (red, green, blue) = pixel(2, 3); // 強調表示したマス
+        assert!(red > 0x80 && green > 0xA0 && blue < 0x20);
+
+        // 余白は白
+        let corner = pixmap.pixel(2, 2).unwrap();
+        assert_eq!((corner.red(), corner.green(), corner.blue()), (0xFF, 0xFF, 0xFF));
+    }
+
+    const SVG_AFTER_F5: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="80" height="80" viewBox="0 0 80 80">
+<rect x="0" y="0" width="80" height="80" fill="#FFF"/>
+<rect x="0" y="0" width="80" height="80" fill="#228B22"/>
+<rect x="30" y="20" width="10" height="10" fill="#FFD700" fill-opacity="0.6"/>
+<line x1="0" y1="0" x2="0" y2="80" stroke="#000" stroke-width="2"/>
+<line x1="0" y1="0" x2="80" y2="0" stroke="#000" stroke-width="2"/>
+<line x1="10" y1="0" x2="10" y2="80" stroke="#000" stroke-width="2"/>
+<line x1="0" y1="10" x2="80" y2="10" stroke="#000" stroke-width="2"/>
+<line x1="20" y1="0" x2="20" y2="80" stroke="#000" stroke-width="2"/>
+<line x1="0" y1="20" x2="80" y2="20" stroke="#000" stroke-width="2"/>
+<line x1="30" y1="0" x2="30" y2="80" stroke="#000" stroke-width="2"/>
+<line x1="0" y1="30" x2="80" y2="30" stroke="#000" stroke-width="2"/>
+<line x1="40" y1="0" x2="40" y2="80" stroke="#000" stroke-width="2"/>
+<line x1="0" y1="40" x2="80" y2="40" stroke="#000" stroke-width="2"/>
+<line x1="50" y1="0" x2="50" y2="80" stroke="#000" stroke-width="2"/>
+<line x1="0" y1="50" x2="80" y2="50" stroke="#000" stroke-width="2"/>
+<line x1="60" y1="0" x2="60" y2="80" stroke="#000" stroke-width="2"/>
+<line x1="0" y1="60" x2="80" y2="60" stroke="#000" stroke-width="2"/>
+<line x1="70" y1="0" x2="70" y2="80" stroke="#000" stroke-width="2"/>
+<line x1="0" y1="70" x2="80" y2="70" stroke="#000" stroke-width="2"/>
+<line x1="80" y1="0" x2="80" y2="80" stroke="#000" stroke-width="2"/>
+<line x1="0" y1="80" x2="80" y2="80" stroke="#000" stroke-width="2"/>
+<circle cx="35" cy="35" r="4" fill="#FFF" stroke="#000" stroke-width="2"/>
+<circle cx="45" cy="35" r="4" fill="#000" stroke="#000" stroke-width="2"/>
+<circle cx="35" cy="45" r="4" fill="#000" stroke="#000" stroke-width="2"/>
+<circle cx="45" cy="45" r="4" fill="#000" stroke="#000" stroke-width="2"/>
+<circle cx="55" cy="45" r="4" fill="#000" stroke="#000" stroke-width="2"/>
+<text x="55" y="45" font-family="sans-serif" font-size="3.5" text-anchor="middle" dominant-baseline="central" fill="#FFF">1</text>
+</svg>
+"##;
+}
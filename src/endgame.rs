@@ -0,0 +1,367 @@
+// 終盤の完全読み
+//
+// 終局まで読み切り、最終的な石差（空きマスは勝った側に数える）を正確に求める。
+// 評価関数を使わないため、対局の振り返りで終盤の手の損失を石数で示すのに使う。
+// 置換表の最善手、角、相手の着手可能数が少ない手の順に調べ（速さ優先）、
+// 2手目以降は幅 1 の窓で調べてから必要なときだけ読み直す（PVS）ことで枝刈りを効かせる。
+// 残りが 4 マス以下になったら並べ替えをやめ、空きマスが奇数の領域（4分割した盤の各象限）の手から調べる（偶数理論）。
+// 相手の確定石から石差の上限を求め、alpha に届かない局面はそれ以上読まない（確定石による枝刈り）。
+
+use crate::bitboard::{squares, Bitboard};
+use crate::game::Game;
+
+// この空きマス数以下の局面は手を並べ替えず置換表も使わずに読む（手間の方が高くつく）
+const LAST_EMPTIES: u32 = 4;
+
+// この空きマス数以上の局面の結果を置換表に保存する
+const TABLE_EMPTIES: u32 = 6;
+
+// Solver::new の置換表の大きさ（2^bits 項目）
+const DEFAULT_TABLE_BITS: u32 = 16;
+
+// 盤を4分割した象限ごとのマスの集合（左上・右上・左下・右下）
+const QUADRANTS: [u64; 4] = [0x0000_0000_0f0f_0f0f, 0x0000_0000_f0f0_f0f0, 0x0f0f_0f0f_0000_0000, 0xf0f0_f0f0_0000_0000];
+
+const CORNERS: u64 = 0x8100_0000_0000_0081;
+
+// 置換表の1項目（局面そのものを持つので衝突しても誤った値は使わない）
+#[derive(Clone, Copy, Default)]
+struct Entry {
+    player: u64,
+    opponent: u64,
+    lower: i8, // 石差の下限
+    upper: i8, // 石差の上限
+    best: u8,  // 最善手（ビット番号、なければ 64）
+}
+
+// 並べ替えのキー（小さいほど先に調べる）を付けた手の一覧（探索中にヒープを確保しないよう固定長の配列に入れる）
+struct MoveList {
+    moves: [(i32, u32); 64],
+    len: usize,
+}
+
+impl MoveList {
+    fn squares(&self) -> impl Iterator<Item = u32> + '_ {
+        self.moves[..self.len].iter().map(|&(_, square)| square)
+    }
+}
+
+// 完全読みの結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Solution {
+    pub score: i32,                         // 手番側から見た最終的な石差
+    pub best_move: Option<(usize, usize)>, // 最善手（打てる手がない場合は None）
+}
+
+// 完全読みの探索
+pub struct Solver {
+    nodes: u64,
+    table: Vec<Entry>,
+    table_bits: u32,
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Solver::new()
+    }
+}
+
+impl Solver {
+    pub fn new() -> Self {
+        Solver::with_table_bits(DEFAULT_TABLE_BITS)
+    }
+
+    // 2^bits 項目の置換表を使う探索（20 空き以上の局面を読む場合は大きくする）
+    pub fn with_table_bits(bits: u32) -> Self {
+        Solver {
+            nodes: 0,
+            table: Vec::new(),
+            table_bits: bits,
+        }
+    }
+
+    // これまでに調べた局面の数
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    // 局面を終局まで読み切り、手番側から見た石差と最善手を求める
+    pub fn solve(&mut self, game: &Game) -> Solution {
+        let board = Bitboard::from_board(game.board_state(), game.current_player());
+        let moves = board.moves();
+        if moves == 0 {
+            self.nodes += 1;
+            let passed = board.pass();
+            let score = if passed.moves() == 0 { board.final_score() } else { -self.search(passed, -64, 64) };
+            return Solution { score, best_move: None };
+        }
+
+        let (mut alpha, beta) = (-65, 64);
+        let mut best_move = None;
+        for (index, square) in self.ordered(board, moves, 64).squares().enumerate() {
+            let next = board.play(square);
+            let mut score = if index == 0 { -self.search(next, -beta, -alpha) } else { -self.search(next, -alpha - 1, -alpha) };
+            if index > 0 && score > alpha {
+                score = -self.search(next, -beta, -alpha);
+            }
+            if score > alpha {
+                alpha = score;
+                best_move = Some((square as usize / 8, square as usize % 8));
+            }
+        }
+        Solution { score: alpha, best_move }
+    }
+
+    // 各有効手（ビット番号）を打った後の手番側から見た最終的な石差
+    pub(crate) fn solve_moves(&mut self, board: Bitboard) -> Vec<(u32, i32)> {
+        squares(board.moves())
+            .map(|square| (square, -self.search(board.play(square), -64, 64)))
+            .collect()
+    }
+
+    // ネガアルファ法（alpha 以下・beta 以上の値は境界の値になる）
+    fn search(&mut self, board: Bitboard, mut alpha: i32, mut beta: i32) -> i32 {
+        self.nodes += 1;
+
+        // 相手の確定石は最後まで相手の石なので、石差は 64 - 2 × 相手の確定石 を超えない。
+        // 相手の石が少なく alpha に届かないことが明らかなときは確定石を数えずに済ませる
+        if alpha >= 64 - 2 * board.opponent.count_ones() as i32 {
+            let upper = 64 - 2 * board.opponent_stable().count_ones() as i32;
+            if upper <= alpha {
+                return upper;
+            }
+        }
+
+        let empties = board.empties();
+        if empties <= LAST_EMPTIES {
+            return self.search_last(board, alpha, beta);
+        }
+
+        let use_table = empties >= TABLE_EMPTIES;
+        let mut hint = 64;
+        if use_table {
+            if let Some(entry) = self.probe(board) {
+                let (lower, upper) = (entry.lower as i32, entry.upper as i32);
+                if lower >= beta || lower == upper {
+                    return lower;
+                }
+                if upper <= alpha {
+                    return upper;
+                }
+                alpha = alpha.max(lower);
+                beta = beta.min(upper);
+                hint = entry.best as u32;
+            }
+        }
+
+        let moves = board.moves();
+        if moves == 0 {
+            let passed = board.pass();
+            if passed.moves() == 0 {
+                return board.final_score();
+            }
+            return -self.search(passed, -beta, -alpha);
+        }
+
+        let original_alpha = alpha;
+        let mut best = -64;
+        let mut best_square = 64;
+        for (index, square) in self.ordered(board, moves, hint).squares().enumerate() {
+            let next = board.play(square);
+            let score = if index == 0 {
+                -self.search(next, -beta, -alpha)
+            } else {
+                // 最初の手より良くないことを幅 1 の窓で確かめ、良ければ読み直す
+                let score = -self.search(next, -alpha - 1, -alpha);
+                if score > alpha && score < beta {
+                    -self.search(next, -beta, -alpha)
+                } else {
+                    score
+                }
+            };
+            if score > best {
+                best = score;
+                best_square = square;
+                if score > alpha {
+                    alpha = score;
+                    if alpha >= beta {
+                        break;
+                    }
+                }
+            }
+        }
+
+        if use_table {
+            let lower = if best > original_alpha { best } else { -64 };
+            let upper = if best < beta { best } else { 64 };
+            self.store(board, lower, upper, best_square);
+        }
+        best
+    }
+
+    // 残りが少ない局面の探索（並べ替えや置換表を使わず、空きマスが奇数の象限の手から調べる）
+    fn search_last(&mut self, board: Bitboard, mut alpha: i32, beta: i32) -> i32 {
+        let empty = !(board.player | board.opponent);
+        if empty.count_ones() == 1 {
+            return last_square(board, empty.trailing_zeros());
+        }
+
+        // 着手可能な手をまとめて求めるより、少ない空きマスごとに裏返る石を調べる方が速い
+        let odd = odd_quadrants(board);
+        let mut best = -65;
+        for square in squares(empty & odd).chain(squares(empty & !odd)) {
+            let flips = board.flips(square);
+            if flips == 0 {
+                continue;
+            }
+            self.nodes += 1;
+            let score = -self.search_last(board.play_flips(square, flips), -beta, -alpha);
+            if score > best {
+                best = score;
+                if score > alpha {
+                    alpha = score;
+                    if alpha >= beta {
+                        break;
+                    }
+                }
+            }
+        }
+
+        if best == -65 {
+            let passed = board.pass();
+            if squares(empty).all(|square| passed.flips(square) == 0) {
+                return board.final_score();
+            }
+            self.nodes += 1;
+            return -self.search_last(passed, -beta, -alpha);
+        }
+        best
+    }
+
+    // 調べる順に並べた手（置換表の最善手、角、相手の着手可能数が少ない順。相手が角に打てる手は重く数える）
+    fn ordered(&self, board: Bitboard, moves: u64, hint: u32) -> MoveList {
+        let mut list = MoveList { moves: [(0, 0); 64], len: 0 };
+        for square in squares(moves) {
+            let key = if square == hint {
+                i32::MIN
+            } else {
+                let replies = board.play(square).moves();
+                let corner = if CORNERS & (1u64 << square) != 0 { -2 } else { 0 };
+                replies.count_ones() as i32 + (replies & CORNERS).count_ones() as i32 + corner
+            };
+            // 挿入ソート（手の数は少ない）
+            let mut index = list.len;
+            while index > 0 && list.moves[index - 1].0 > key {
+                list.moves[index] = list.moves[index - 1];
+                index -= 1;
+            }
+            list.moves[index] = (key, square);
+            list.len += 1;
+        }
+        list
+    }
+
+    fn index(&self, board: Bitboard) -> usize {
+        let hash = (board.player ^ board.opponent.rotate_left(23)).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        (hash >> (64 - self.table_bits)) as usize
+    }
+
+    fn probe(&self, board: Bitboard) -> Option<Entry> {
+        let entry = *self.table.get(self.index(board))?;
+        (entry.player == board.player && entry.opponent == board.opponent).then_some(entry)
+    }
+
+    // 常に上書きする（置換表は最初に保存するときに確保する）
+    fn store(&mut self, board: Bitboard, lower: i32, upper: i32, best: u32) {
+        if self.table.is_empty() {
+            self.table = vec![Entry::default(); 1 << self.table_bits];
+        }
+        let index = self.index(board);
+        self.table[index] = Entry {
+            player: board.player,
+            opponent: board.opponent,
+            lower: lower as i8,
+            upper: upper as i8,
+            best: best as u8,
+        };
+    }
+}
+
+// 空きマスが1つ（square）だけの局面の最終的な石差
+fn last_square(board: Bitboard, square: u32) -> i32 {
+    if board.flips(square) != 0 {
+        return -board.play(square).final_score();
+    }
+    let passed = board.pass();
+    if passed.flips(square) != 0 {
+        return passed.play(square).final_score();
+    }
+    board.final_score()
+}
+
+// 空きマスが奇数の象限のマスの集合
+fn odd_quadrants(board: Bitboard) -> u64 {
+    let empty = !(board.player | board.opponent);
+    QUADRANTS.iter().filter(|&&quadrant| (quadrant & empty).count_ones() % 2 == 1).fold(0, |odd, &quadrant| odd | quadrant)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::testing::random_positions;
+
+    // 枝刈りをアルファベータ法だけにした完全読み
+    fn reference(board: Bitboard, mut alpha: i32, beta: i32) -> i32 {
+        let moves = board.moves();
+        if moves == 0 {
+            let passed = board.pass();
+            if passed.moves() == 0 {
+                return board.final_score();
+            }
+            return -reference(passed, -beta, -alpha);
+        }
+        for square in squares(moves) {
+            alpha = alpha.max(-reference(board.play(square), -beta, -alpha));
+            if alpha >= beta {
+                break;
+            }
+        }
+        alpha
+    }
+
+    // 終盤（空きマス 10 以下）の乱数の局面
+    fn endgame_positions(count: usize) -> Vec<Game> {
+        random_positions(4000, 7)
+            .into_iter()
+            .map(|(board, player)| Game::from_position(&board.to_position_string(player)).unwrap())
+            .filter(|game| Bitboard::from_board(game.board_state(), game.current_player()).empties() <= 10)
+            .take(count)
+            .collect()
+    }
+
+    #[test]
+    fn solve_matches_reference() {
+        for game in endgame_positions(40) {
+            let board = Bitboard::from_board(game.board_state(), game.current_player());
+            let expected = reference(board, -64, 64);
+            // 小さい置換表では項目の上書きが頻繁に起きる
+            for mut solver in [Solver::new(), Solver::with_table_bits(4)] {
+                let solution = solver.solve(&game);
+                assert_eq!(solution.score, expected, "{}", game.position());
+
+                let (row, col) = solution.best_move.unwrap();
+                assert_eq!(-reference(board.play((row * 8 + col) as u32), -64, 64), expected, "{}", game.position());
+            }
+        }
+    }
+
+    #[test]
+    fn solve_moves_matches_reference() {
+        for game in endgame_positions(20) {
+            let board = Bitboard::from_board(game.board_state(), game.current_player());
+            for (square, score) in Solver::new().solve_moves(board) {
+                assert_eq!(score, -reference(board.play(square), -64, 64), "{}", game.position());
+            }
+        }
+    }
+}
@@ -0,0 +1,420 @@
+// 着手の説明文
+//
+// 手を打つ前後の局面の評価値の内訳（EvalBreakdown）を AI と同じ重みで比べ、
+// 評価値を大きく動かした項目から順に、その手を選んだ理由（または危険）を日本語または英語の文章にする。
+// 重みが 0 の項目は評価値を動かさないため説明にも現れず、説明が AI の判断と食い違わない。
+
+use std::str::FromStr;
+
+use crate::ai::{EvalWeights, Search};
+use crate::board::BoardState;
+use crate::game::square_name;
+use crate::stability;
+
+const CORNERS: [(usize, usize); 4] = [(0, 0), (0, 7), (7, 0), (7, 7)];
+
+// 説明に使う理由の数の上限（評価値への寄与の大きい順）
+const MAX_REASONS: usize = 3;
+
+// 説明文の言語
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Japanese,
+    English,
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    // "ja" / "en"（"japanese" / "english" も可）
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "ja" | "jp" | "japanese" => Ok(Language::Japanese),
+            "en" | "english" => Ok(Language::English),
+            _ => Err(format!("不明な言語です: {}", text)),
+        }
+    }
+}
+
+// 手を打つ前後の変化
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MoveFeatures {
+    pub row: usize,
+    pub col: usize,
+    // 評価値の内訳の項目ごとの変化（打った側の視点、重みを掛けた後の値）。名前は EvalBreakdown::terms と同じで、
+    // discs には material を含める。X マスを避けた場合は "avoided_x_squares" に避けた X マスの重みが入る。
+    // 0 の項目は含めず、絶対値の大きい順に並ぶ
+    pub contributions: Vec<(&'static str, i32)>,
+    pub flips: usize,                           // 返す石の数
+    pub x_square: bool,                         // 空いている角の斜め隣（X マス）に打つ
+    pub c_square: bool,                         // 空いている角の隣の辺（C マス）に打つ
+    pub avoided_x_squares: Vec<(usize, usize)>, // 打てたのに避けた X マス（x_squares の重みが正の場合のみ）
+    pub mobility_before: usize,                 // 打つ前の自分の着手可能数
+    pub mobility_after: usize,                  // 打った後の自分の着手可能数
+    pub opponent_mobility_before: usize,        // 打つ前の相手の着手可能数
+    pub opponent_mobility_after: usize,         // 打った後の相手の着手可能数
+    pub frontier_after: usize,                  // 打った後の自分のフロンティア（空きマスに接する石）の数
+    pub opponent_frontier_after: usize,         // 打った後の相手のフロンティアの数
+    pub stable_before: usize,                   // 打つ前の自分の確定石の数
+    pub stable_after: usize,                    // 打った後の自分の確定石の数
+    pub opponent_stable_before: usize,          // 打つ前の相手の確定石の数
+    pub opponent_stable_after: usize,           // 打った後の相手の確定石の数
+}
+
+impl MoveFeatures {
+    // 項目の評価値の変化（含まれない項目は 0）
+    pub fn contribution(&self, name: &str) -> i32 {
+        self.contributions.iter().find(|&&(term, _)| term == name).map_or(0, |&(_, value)| value)
+    }
+}
+
+// 盤面の手番 player が (row, col) に打つ手の前後の変化を、評価関数の重み weights で調べる
+pub(crate) fn move_features(board: &BoardState, player: i8, row: usize, col: usize, weights: EvalWeights) -> MoveFeatures {
+    let opponent = 3 - player;
+    let moves = board.get_valid_moves_for_board(player);
+    let mut after = board.clone();
+    after.make_move_on_board(row, col, player);
+
+    // 打った後に次に打つのは相手（相手がパスなら自分）
+    let next = if after.get_valid_moves_for_board(opponent).is_empty() && !after.get_valid_moves_for_board(player).is_empty() {
+        player
+    } else {
+        opponent
+    };
+    let search = Search::with_weights(player, weights);
+    let before_terms = search.evaluate_breakdown(board, player, player).terms();
+    let after_terms = search.evaluate_breakdown(&after, player, next).terms();
+
+    let mut contributions: Vec<(&'static str, i32)> = Vec::new();
+    for (&(name, before), &(_, after)) in before_terms.iter().zip(after_terms.iter()) {
+        let name = if name == "material" { "discs" } else { name };
+        match contributions.iter_mut().find(|(term, _)| *term == name) {
+            Some((_, value)) => *value += after - before,
+            None => contributions.push((name, after - before)),
+        }
+    }
+
+    let x_square = is_x_square(board, row, col);
+    let avoided_x_squares: Vec<(usize, usize)> = if weights.x_squares > 0 && !x_square {
+        moves.iter().copied().filter(|&(r, c)| is_x_square(board, r, c)).collect()
+    } else {
+        Vec::new()
+    };
+    if !avoided_x_squares.is_empty() {
+        contributions.push(("avoided_x_squares", weights.x_squares));
+    }
+    contributions.retain(|&(_, value)| value != 0);
+    contributions.sort_by_key(|&(_, value)| std::cmp::Reverse(value.abs())); // 安定ソートなので同じ大きさなら内訳の順
+
+    MoveFeatures {
+        row,
+        col,
+        contributions,
+        flips: board.get_flips(row, col, player).len(),
+        x_square,
+        c_square: is_c_square(board, row, col),
+        avoided_x_squares,
+        mobility_before: moves.len(),
+        mobility_after: after.get_valid_moves_for_board(player).len(),
+        opponent_mobility_before: board.get_valid_moves_for_board(opponent).len(),
+        opponent_mobility_after: after.get_valid_moves_for_board(opponent).len(),
+        frontier_after: frontier(&after, player),
+        opponent_frontier_after: frontier(&after, opponent),
+        stable_before: stability::count_stable(board, player),
+        stable_after: stability::count_stable(&after, player),
+        opponent_stable_before: stability::count_stable(board, opponent),
+        opponent_stable_after: stability::count_stable(&after, opponent),
+    }
+}
+
+// 空いている角の斜め隣のマスか
+fn is_x_square(board: &BoardState, row: usize, col: usize) -> bool {
+    CORNERS.iter().any(|&(corner_row, corner_col)| {
+        board.board[corner_row][corner_col] == 0 && row.abs_diff(corner_row) == 1 && col.abs_diff(corner_col) == 1
+    })
+}
+
+// 空いている角の辺の隣のマスか
+fn is_c_square(board: &BoardState, row: usize, col: usize) -> bool {
+    CORNERS.iter().any(|&(corner_row, corner_col)| {
+        board.board[corner_row][corner_col] == 0 && row.abs_diff(corner_row) + col.abs_diff(corner_col) == 1
+    })
+}
+
+// player の石のうち空きマスに接するもの（フロンティア）の数
+fn frontier(board: &BoardState, player: i8) -> usize {
+    let next_to_empty = |row: usize, col: usize| {
+        (row.saturating_sub(1)..=(row + 1).min(7)).any(|r| (col.saturating_sub(1)..=(col + 1).min(7)).any(|c| board.board[r][c] == 0))
+    };
+    (0..64).filter(|&square| board.board[square / 8][square % 8] == player && next_to_empty(square / 8, square % 8)).count()
+}
+
+// 評価値の1項目の変化を説明する文（説明しない変化の場合は None）
+fn reason(features: &MoveFeatures, term: &str, value: i32, language: Language) -> Option<String> {
+    let ja = language == Language::Japanese;
+    let square = square_name(features.row, features.col);
+    let text = match (term, value > 0) {
+        ("corners", true) => if ja { format!("角 ({}) を取ります", square) } else { format!("Takes the corner {}", square) },
+        ("x_squares", false) if features.x_square => if ja {
+            format!("X マス ({}) に打つため、角を取られる危険があります", square)
+        } else {
+            format!("Plays the X-square {}, which risks giving away the corner", square)
+        },
+        ("x_squares", false) => if ja {
+            "空いている角の斜め隣 (X マス) の石が自分の色になり、角を取られる危険があります".to_string()
+        } else {
+            "Turns a disc on an X-square next to an empty corner, which risks giving away the corner".to_string()
+        },
+        ("x_squares", true) | ("c_squares", true) => if ja {
+            "角が埋まり、隣のマスの石が角を取られる足場ではなくなります".to_string()
+        } else {
+            "Fills the corner, so the discs next to it are no longer a risk".to_string()
+        },
+        ("avoided_x_squares", _) => {
+            let names: Vec<String> = features.avoided_x_squares.iter().map(|&(r, c)| square_name(r, c)).collect();
+            if ja {
+                format!("角を取られないよう X マス ({}) を避けます", names.join(", "))
+            } else {
+                format!("Avoids the X-square {} so the corner stays safe", names.join(", "))
+            }
+        }
+        ("c_squares", false) if features.c_square => if ja {
+            format!("C マス ({}) に打つため、角を取られる足場になります", square)
+        } else {
+            format!("Plays the C-square {}, which can help the opponent take the corner", square)
+        },
+        ("c_squares", false) => if ja {
+            "空いている角の隣 (C マス) の石が自分の色になり、角を取られる足場になります".to_string()
+        } else {
+            "Turns a disc on a C-square next to an empty corner, which can help the opponent take the corner".to_string()
+        },
+        ("stability", true) => {
+            let gain = features.stable_after.saturating_sub(features.stable_before);
+            if ja { format!("確定石が {} 個増えます", gain) } else { format!("Gains {} stable disc{}", gain, plural(gain)) }
+        }
+        ("stability", false) => {
+            let gain = features.opponent_stable_after.saturating_sub(features.opponent_stable_before);
+            if ja {
+                format!("相手の確定石が {} 個増えます", gain)
+            } else {
+                format!("Gives the opponent {} stable disc{}", gain, plural(gain))
+            }
+        }
+        ("mobility", true) if features.opponent_mobility_after == 0 && features.mobility_after > 0 => if ja {
+            "相手は打てる場所がなくパスになります".to_string()
+        } else {
+            "Leaves the opponent with no move, forcing a pass".to_string()
+        },
+        ("mobility", true) if features.opponent_mobility_after < features.opponent_mobility_before => if ja {
+            format!("相手の打てる場所を {} から {} に減らします", features.opponent_mobility_before, features.opponent_mobility_after)
+        } else {
+            format!("Cuts the opponent's mobility from {} to {}", features.opponent_mobility_before, features.opponent_mobility_after)
+        },
+        ("mobility", true) => if ja {
+            format!("自分の打てる場所が {} から {} に増えます", features.mobility_before, features.mobility_after)
+        } else {
+            format!("Increases its own mobility from {} to {}", features.mobility_before, features.mobility_after)
+        },
+        ("mobility", false) => if ja {
+            format!("相手の打てる場所が {} から {} に増えます", features.opponent_mobility_before, features.opponent_mobility_after)
+        } else {
+            format!("Gives the opponent more moves ({} instead of {})", features.opponent_mobility_after, features.opponent_mobility_before)
+        },
+        ("frontier", true) => if ja {
+            format!("空きマスに接する石 (フロンティア) を相手より少なく保ちます (自分 {} 個、相手 {} 個)", features.frontier_after, features.opponent_frontier_after)
+        } else {
+            format!("Keeps fewer frontier discs than the opponent ({} against {})", features.frontier_after, features.opponent_frontier_after)
+        },
+        ("frontier", false) => if ja {
+            format!("空きマスに接する石 (フロンティア) が増え、相手に打つ場所を与えます (自分 {} 個、相手 {} 個)", features.frontier_after, features.opponent_frontier_after)
+        } else {
+            format!("Adds frontier discs that give the opponent moves ({} against {})", features.frontier_after, features.opponent_frontier_after)
+        },
+        ("potential_mobility", true) => if ja {
+            "相手の石に接する空きマスが増え、この先打てる場所が増えます".to_string()
+        } else {
+            "Opens empty squares next to the opponent's discs for later moves".to_string()
+        },
+        ("potential_mobility", false) => if ja {
+            "自分の石に接する空きマスが増え、相手がこの先打てる場所が増えます".to_string()
+        } else {
+            "Opens empty squares next to its own discs that the opponent can use later".to_string()
+        },
+        ("parity", true) => if ja {
+            "最後の一手を自分が打てる形にします (偶数理論)".to_string()
+        } else {
+            "Keeps the last move for itself (parity)".to_string()
+        },
+        ("parity", false) => if ja {
+            "最後の一手を相手に渡す形になります (偶数理論)".to_string()
+        } else {
+            "Hands the last move to the opponent (parity)".to_string()
+        },
+        ("discs", true) => if ja {
+            format!("石を {} 個返して石差を広げます", features.flips)
+        } else {
+            format!("Flips {} disc{} to widen the disc count", features.flips, plural(features.flips))
+        },
+        ("discs", false) => if ja {
+            format!("石を {} 個返しますが、石を取りすぎない方針に反します", features.flips)
+        } else {
+            format!("Flips {} disc{}, more than this style likes to take", features.flips, plural(features.flips))
+        },
+        ("edges", true) => if ja { "辺の石が増えます".to_string() } else { "Adds discs on the edges".to_string() },
+        ("position", true) => if ja { format!("位置の良いマス ({}) に打ちます", square) } else { format!("Plays the strong square {}", square) },
+        ("position", false) => if ja { format!("位置の悪いマス ({}) に打ちます", square) } else { format!("Plays the weak square {}", square) },
+        _ => return None,
+    };
+    Some(text)
+}
+
+// 変化から説明文を作る（評価値への寄与の大きい理由から順に、最大 MAX_REASONS 個）
+pub fn describe(features: &MoveFeatures, language: Language) -> Vec<String> {
+    let mut reasons: Vec<String> = features
+        .contributions
+        .iter()
+        .filter_map(|&(term, value)| reason(features, term, value, language))
+        .take(MAX_REASONS)
+        .collect();
+
+    if reasons.is_empty() {
+        let square = square_name(features.row, features.col);
+        reasons.push(match language {
+            Language::Japanese => format!("{} に打ち、石を {} 個返します", square, features.flips),
+            Language::English => format!("Plays {} and flips {} disc{}", square, features.flips, plural(features.flips)),
+        });
+    }
+    reasons
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 { "" } else { "s" }
+}
+
+// 説明文を1つの文章にまとめる
+pub fn explanation_text(features: &MoveFeatures, language: Language) -> String {
+    let reasons = describe(features, language);
+    match language {
+        Language::Japanese => format!("{}。", reasons.join("。")),
+        Language::English => format!("{}.", reasons.join(". ")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(rows: &[&str; 8]) -> BoardState {
+        BoardState::from_position_string(&format!("{} X", rows.concat())).unwrap().0
+    }
+
+    fn no_weights() -> EvalWeights {
+        EvalWeights {
+            position: 0,
+            mobility: 0,
+            discs: 0,
+            corners: 0,
+            edges: 0,
+            stability: 0,
+            material: 0,
+            frontier: 0,
+            potential_mobility: 0,
+            parity: 0,
+            x_squares: 0,
+            c_squares: 0,
+        }
+    }
+
+    fn features() -> MoveFeatures {
+        MoveFeatures {
+            row: 0,
+            col: 0,
+            contributions: vec![("corners", 250), ("mobility", -30), ("stability", 80), ("position", 1)],
+            flips: 2,
+            mobility_before: 5,
+            mobility_after: 6,
+            opponent_mobility_before: 4,
+            opponent_mobility_after: 7,
+            stable_before: 0,
+            stable_after: 3,
+            ..MoveFeatures::default()
+        }
+    }
+
+    #[test]
+    fn describe_in_japanese() {
+        assert_eq!(
+            describe(&features(), Language::Japanese),
+            vec!["角 (a1) を取ります", "相手の打てる場所が 4 から 7 に増えます", "確定石が 3 個増えます"]
+        );
+        assert_eq!(
+            explanation_text(&MoveFeatures { contributions: Vec::new(), ..features() }, Language::Japanese),
+            "a1 に打ち、石を 2 個返します。"
+        );
+    }
+
+    #[test]
+    fn describe_in_english() {
+        assert_eq!(
+            describe(&features(), Language::English),
+            vec!["Takes the corner a1", "Gives the opponent more moves (7 instead of 4)", "Gains 3 stable discs"]
+        );
+        assert_eq!(
+            explanation_text(&MoveFeatures { contributions: Vec::new(), ..features() }, Language::English),
+            "Plays a1 and flips 2 discs."
+        );
+    }
+
+    #[test]
+    fn contributions_are_ranked_by_size() {
+        let board = board(&["-OX-----", "--------", "--------", "---OX---", "---XO---", "--------", "--------", "--------"]);
+        let features = move_features(&board, 1, 0, 0, EvalWeights::default());
+        let sizes: Vec<i32> = features.contributions.iter().map(|&(_, value)| value.abs()).collect();
+        assert!(sizes.windows(2).all(|pair| pair[0] >= pair[1]));
+        assert!(features.contribution("corners") > 0);
+        assert!(describe(&features, Language::English).contains(&"Takes the corner a1".to_string()));
+    }
+
+    #[test]
+    fn x_squares_are_only_mentioned_with_their_weight() {
+        let board = board(&["--------", "--------", "--O-----", "---OX---", "---XX---", "--------", "--------", "--------"]);
+        let moves = board.get_valid_moves_for_board(1);
+        assert!(moves.contains(&(1, 1)));
+        let &(row, col) = moves.iter().find(|&&square| square != (1, 1)).unwrap();
+
+        let features = move_features(&board, 1, row, col, EvalWeights::default());
+        assert_eq!(features.avoided_x_squares, vec![(1, 1)]);
+        assert!(describe(&features, Language::English).iter().any(|reason| reason.contains("Avoids the X-square b2")));
+
+        let weights = EvalWeights { x_squares: 0, ..EvalWeights::default() };
+        let features = move_features(&board, 1, row, col, weights);
+        assert!(features.avoided_x_squares.is_empty());
+        assert_eq!(features.contribution("x_squares"), 0);
+        assert!(describe(&features, Language::English).iter().all(|reason| !reason.contains("X-square")));
+    }
+
+    #[test]
+    fn frontier_is_explained_with_its_weight() {
+        // 中盤（20 石以上）まで最初の有効な手を打ち進める
+        let mut board = board(&["--------", "--------", "--------", "---OX---", "---XO---", "--------", "--------", "--------"]);
+        let mut player = 1;
+        while board.board.iter().flatten().filter(|&&cell| cell != 0).count() < 24 {
+            if let Some(&(row, col)) = board.get_valid_moves_for_board(player).first() {
+                board.make_move_on_board(row, col, player);
+            }
+            player = 3 - player;
+        }
+        if board.get_valid_moves_for_board(player).is_empty() {
+            player = 3 - player;
+        }
+
+        let weights = EvalWeights { frontier: 15, ..no_weights() };
+        let mut mentioned = false;
+        for (row, col) in board.get_valid_moves_for_board(player) {
+            let features = move_features(&board, player, row, col, weights);
+            assert!(features.contributions.iter().all(|&(term, _)| term == "frontier"));
+            mentioned |= describe(&features, Language::Japanese).iter().any(|reason| reason.contains("フロンティア"));
+        }
+        assert!(mentioned);
+    }
+}
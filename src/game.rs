@@ -0,0 +1,312 @@
+// Canvas を使わない対局の進行
+//
+// ネイティブのコマンドラインツールやエンジンプロトコルから利用するための
+// 対局状態（盤面・手番・棋譜）と、待った・棋譜の出力などの操作をまとめる。
+// パスは着手時に自動で処理する。
+
+use std::fmt::Write;
+
+use crate::ai::{self, AiConfig, AiDifficulty, EvalBreakdown, EvalWeights, Search};
+use crate::board::BoardState;
+use crate::diagram::MoveRecord;
+use crate::explain::{self, Language, MoveFeatures};
+use crate::review::{self, GameReview, ReviewOptions};
+use crate::stability;
+
+// 列の座標ラベル
+const COLUMN_LABELS: [char; 8] = ['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h'];
+
+// 座標表記（"d3" など）をマスに変換
+pub fn parse_square(text: &str) -> Option<(usize, usize)> {
+    let mut chars = text.trim().chars();
+    let col = match chars.next()?.to_ascii_lowercase() {
+        c @ 'a'..='h' => c as usize - 'a' as usize,
+        _ => return None,
+    };
+    let row = match chars.next()? {
+        c @ '1'..='8' => c as usize - '1' as usize,
+        _ => return None,
+    };
+    if chars.next().is_some() {
+        return None;
+    }
+    Some((row, col))
+}
+
+// マスを座標表記（"d3" など）に変換
+pub fn square_name(row: usize, col: usize) -> String {
+    format!("{}{}", COLUMN_LABELS[col], row + 1)
+}
+
+// 対局の状態
+#[derive(Clone)]
+pub struct Game {
+    initial: (BoardState, i8), // 開始局面と手番（待ったで並べ直す際に使う）
+    board: BoardState,
+    current_player: i8, // 1: 黒, 2: 白
+    history: Vec<MoveRecord>,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Game::new()
+    }
+}
+
+impl Game {
+    // 初期配置から対局を開始
+    pub fn new() -> Self {
+        let mut board = BoardState { board: [[0; 8]; 8] };
+        board.board[3][3] = 2; // 白
+        board.board[3][4] = 1; // 黒
+        board.board[4][3] = 1; // 黒
+        board.board[4][4] = 2; // 白
+        Game::from_state(board, 1)
+    }
+
+    // 局面文字列（64マス分の X / O / - と手番）から対局を開始
+    pub fn from_position(position: &str) -> Result<Self, String> {
+        let (board, player) = BoardState::from_position_string(position)?;
+        Ok(Game::from_state(board, player))
+    }
+
+    fn from_state(board: BoardState, player: i8) -> Self {
+        let mut game = Game {
+            initial: (board.clone(), player),
+            board,
+            current_player: player,
+            history: Vec::new(),
+        };
+        game.skip_pass();
+        game
+    }
+
+    // 現在の局面を文字列として出力
+    pub fn position(&self) -> String {
+        self.board.to_position_string(self.current_player)
+    }
+
+    // 盤面（0: 空, 1: 黒, 2: 白）
+    pub fn board(&self) -> &[[i8; 8]; 8] {
+        &self.board.board
+    }
+
+    // 手番のプレイヤー（1: 黒, 2: 白）
+    pub fn current_player(&self) -> i8 {
+        self.current_player
+    }
+
+    // 着手履歴（行, 列, 手番）
+    pub fn history(&self) -> &[MoveRecord] {
+        &self.history
+    }
+
+    // 開始局面から棋譜を並べ直し、各手を打つ前の局面とその手を返す（対局の振り返り用）
+    pub fn replay(&self) -> Vec<(Game, MoveRecord)> {
+        let mut game = Game::from_state(self.initial.0.clone(), self.initial.1);
+        let mut positions = Vec::new();
+        for &(row, col, player) in &self.history {
+            positions.push((game.clone(), (row, col, player)));
+            if game.play(row, col).is_err() {
+                break;
+            }
+        }
+        positions
+    }
+
+    pub(crate) fn board_state(&self) -> &BoardState {
+        &self.board
+    }
+
+    // 手番のプレイヤーの有効な手
+    pub fn valid_moves(&self) -> Vec<(usize, usize)> {
+        if self.is_game_over() {
+            return Vec::new();
+        }
+        self.board.get_valid_moves_for_board(self.current_player)
+    }
+
+    // 指定したプレイヤーに有効な手があるか（手番でなくても盤面から判定する）
+    pub fn has_moves(&self, player: i8) -> bool {
+        !self.is_game_over() && !self.board.get_valid_moves_for_board(player).is_empty()
+    }
+
+    // 手番のプレイヤーの石を置く（相手に有効な手がない場合は自動でパスする）
+    pub fn play(&mut self, row: usize, col: usize) -> Result<(), String> {
+        if row >= 8 || col >= 8 || self.is_game_over() || !self.board.is_valid_move_for_board(row, col, self.current_player) {
+            return Err(format!("{} には置けません", square_name(row.min(7), col.min(7))));
+        }
+
+        self.board.make_move_on_board(row, col, self.current_player);
+        self.history.push((row, col, self.current_player));
+        self.current_player = 3 - self.current_player;
+        self.skip_pass();
+        Ok(())
+    }
+
+    // 手番のプレイヤーに有効な手がなければ相手に手番を渡す
+    fn skip_pass(&mut self) {
+        if !self.board.is_terminal_state() && self.board.get_valid_moves_for_board(self.current_player).is_empty() {
+            self.current_player = 3 - self.current_player;
+        }
+    }
+
+    // 直前の1手を取り消す。取り消した手を返す
+    pub fn undo(&mut self) -> Option<MoveRecord> {
+        let undone = self.history.pop()?;
+
+        // 開始局面から並べ直す（パスの有無は棋譜の手番から分かる）
+        self.board = self.initial.0.clone();
+        for &(row, col, player) in &self.history {
+            self.board.make_move_on_board(row, col, player);
+        }
+        self.current_player = undone.2;
+        Some(undone)
+    }
+
+    // 対局が終了しているか
+    pub fn is_game_over(&self) -> bool {
+        self.board.is_terminal_state()
+    }
+
+    // player の確定石（この先二度と返されない石）のマス（左上から順）
+    pub fn stable_discs(&self, player: i8) -> Vec<(usize, usize)> {
+        stability::stable_squares(&self.board, player)
+    }
+
+    // 石の数（黒, 白）
+    pub fn score(&self) -> (i32, i32) {
+        let mut black = 0;
+        let mut white = 0;
+        for row in self.board.board.iter() {
+            for &piece in row.iter() {
+                match piece {
+                    1 => black += 1,
+                    2 => white += 1,
+                    _ => {}
+                }
+            }
+        }
+        (black, white)
+    }
+
+    // 現在の局面の評価値の内訳（既定の重み、player の視点）
+    pub fn evaluation_breakdown(&self, player: i8) -> EvalBreakdown {
+        self.evaluation_breakdown_with(player, EvalWeights::default())
+    }
+
+    // 指定した重みでの現在の局面の評価値の内訳（player の視点）
+    pub fn evaluation_breakdown_with(&self, player: i8, weights: EvalWeights) -> EvalBreakdown {
+        Search::with_weights(player, weights).evaluate_breakdown(&self.board, player, self.current_player)
+    }
+
+    // 難易度に応じた戦略で各有効手を評価
+    pub fn evaluate_moves(&self, difficulty: AiDifficulty) -> Vec<((usize, usize), i32)> {
+        self.evaluate_moves_with(difficulty)
+    }
+
+    // 指定した設定（探索方法・評価関数の重み・持ち時間）で各有効手を評価（評価値は手番のプレイヤーの視点）
+    pub fn evaluate_moves_with(&self, config: impl Into<AiConfig>) -> Vec<((usize, usize), i32)> {
+        if self.is_game_over() {
+            return Vec::new();
+        }
+        ai::evaluate_moves(&self.board, self.current_player, &config.into())
+    }
+
+    // 難易度に応じたAIの手（有効な手がない場合は None）
+    pub fn ai_move(&self, difficulty: AiDifficulty) -> Option<(usize, usize)> {
+        self.ai_move_with(difficulty)
+    }
+
+    // 指定した設定でのAIの手（有効な手がない場合は None）
+    pub fn ai_move_with(&self, config: impl Into<AiConfig>) -> Option<(usize, usize)> {
+        let config = config.into();
+        ai::choose_move(&self.board, self.current_player, &self.evaluate_moves_with(config), &config)
+    }
+
+    // 手番のプレイヤーが (row, col) に打つ手の前後の変化（有効な手でない場合は None）
+    pub fn move_features(&self, row: usize, col: usize) -> Option<MoveFeatures> {
+        self.move_features_with(row, col, EvalWeights::default())
+    }
+
+    // 評価関数の重みを指定して手の前後の変化を調べる（有効な手でない場合は None）
+    pub fn move_features_with(&self, row: usize, col: usize, weights: EvalWeights) -> Option<MoveFeatures> {
+        if !self.valid_moves().contains(&(row, col)) {
+            return None;
+        }
+        Some(explain::move_features(&self.board, self.current_player, row, col, weights))
+    }
+
+    // 手番のプレイヤーが (row, col) に打つ手の説明文（有効な手でない場合は None）
+    pub fn explain_move(&self, row: usize, col: usize, language: Language) -> Option<String> {
+        self.explain_move_with(row, col, language, EvalWeights::default())
+    }
+
+    // 評価関数の重みを指定した説明文（AI の手はその AI の重みで説明する）
+    pub fn explain_move_with(&self, row: usize, col: usize, language: Language, weights: EvalWeights) -> Option<String> {
+        self.move_features_with(row, col, weights).map(|features| explain::explanation_text(&features, language))
+    }
+
+    // 各手を AI の最善手と比べて振り返る
+    pub fn review(&self) -> GameReview {
+        self.review_with(&ReviewOptions::default())
+    }
+
+    // 探索の深さと完全読みを始める空きマス数を指定して振り返る
+    pub fn review_with(&self, options: &ReviewOptions) -> GameReview {
+        review::review_game(self, options)
+    }
+
+    // 棋譜を "f5d6c3..." の形式で出力（パスは省略される）
+    pub fn transcript(&self) -> String {
+        self.history.iter().map(|&(row, col, _)| square_name(row, col)).collect()
+    }
+
+    // 棋譜文字列の手を順に打つ（開始局面からの続きとして）
+    pub fn play_transcript(&mut self, transcript: &str) -> Result<(), String> {
+        let chars: Vec<char> = transcript.chars().filter(|c| !c.is_whitespace()).collect();
+        if !chars.len().is_multiple_of(2) {
+            return Err(format!("棋譜の長さが不正です: {}", transcript));
+        }
+        for pair in chars.chunks(2) {
+            let text: String = pair.iter().collect();
+            let (row, col) = parse_square(&text).ok_or_else(|| format!("不正な座標です: {}", text))?;
+            self.play(row, col)?;
+        }
+        Ok(())
+    }
+
+    // 盤面をテキストで表示（show_moves が true の場合は有効な手を * で示す）
+    pub fn to_ascii(&self, show_moves: bool) -> String {
+        let moves = if show_moves { self.valid_moves() } else { Vec::new() };
+        let last = self.history.last().map(|&(row, col, _)| (row, col));
+
+        let mut text = String::from("  a b c d e f g h\n");
+        for row in 0..8 {
+            let _ = write!(text, "{}", row + 1);
+            for col in 0..8 {
+                let piece = match self.board.board[row][col] {
+                    1 => 'X',
+                    2 => 'O',
+                    _ if moves.contains(&(row, col)) => '*',
+                    _ => '.',
+                };
+                // 直前の手は括弧で囲む
+                let separator = if last == Some((row, col)) {
+                    '('
+                } else if col > 0 && last == Some((row, col - 1)) {
+                    ')'
+                } else {
+                    ' '
+                };
+                text.push(separator);
+                text.push(piece);
+            }
+            if last == Some((row, 7)) {
+                text.push(')');
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
@@ -0,0 +1,372 @@
+// 少しずつ進められる探索
+//
+// 探索を明示的なスタックで実装し、step(max_nodes) で指定したノード数だけ進める。
+// JavaScript 側は描画の合間に step を呼ぶことで、スレッドを使わずに
+// メインスレッド上で探索と描画を交互に行い、いつでも中断できる。
+// 評価値は ai::Search の再帰版と同じ値になる。
+
+use wasm_bindgen::prelude::*;
+
+use crate::ai::{self, AiConfig, AiDifficulty, Search, Strategy, INFINITY};
+use crate::board::BoardState;
+
+// 探索中のノード（再帰版の1回の呼び出しに相当）
+struct Frame {
+    board: BoardState,
+    depth: u8,
+    alpha: i32,
+    beta: i32,
+    player: i8,        // 手番（評価値はこのプレイヤーの視点）
+    sign: i32,         // 親ノードに返すときに掛ける符号（パスした回数が奇数なら -1）
+    moves: Vec<(usize, usize)>,
+    next: usize,
+    best: i32,
+    hash: u64,         // 置換表用のハッシュ値
+    window: (i32, i32), // ノードに入ったときの窓（置換表への格納用）
+}
+
+// ノードに入った結果
+enum Entered {
+    Value(i32), // 末端ノードだったので評価値（入ったときの手番の視点）が確定した
+    Frame(Frame),
+}
+
+// ノード数を指定して少しずつ進められる探索の本体
+pub(crate) struct SteppedSearch {
+    board: BoardState,
+    player: i8,
+    config: AiConfig,
+    strategy: Strategy,
+    search: Search,
+    root_moves: Vec<(usize, usize)>,
+    root_index: usize,
+    evaluations: Vec<((usize, usize), i32)>,
+    stack: Vec<Frame>,
+    nodes: u64,
+    cancelled: bool,
+}
+
+// AiConfig の形式の設定文字列を SteppedSearch 用に解釈する（ノード数で区切って進めるため、持ち時間の time= は使えない）
+pub(crate) fn parse_config(config: &str) -> Result<AiConfig, String> {
+    let config = config.parse::<AiConfig>()?;
+    if config.time_limit.is_some() {
+        return Err("time= はネイティブ環境でのみ指定できます".to_string());
+    }
+    Ok(config)
+}
+
+impl SteppedSearch {
+    pub(crate) fn new(board: BoardState, player: i8, config: AiConfig) -> Self {
+        let root_moves = board.get_valid_moves_for_board(player);
+        SteppedSearch {
+            board,
+            player,
+            config,
+            strategy: config.strategy,
+            search: Search::with_weights(player, config.weights),
+            root_moves,
+            root_index: 0,
+            evaluations: Vec::new(),
+            stack: Vec::new(),
+            nodes: 0,
+            cancelled: false,
+        }
+    }
+
+    // 探索が終了したか（すべての手を評価し終えたか、中断された）
+    pub(crate) fn is_finished(&self) -> bool {
+        self.cancelled || self.root_index >= self.root_moves.len()
+    }
+
+    // 探索を中断
+    pub(crate) fn cancel(&mut self) {
+        self.cancelled = true;
+        self.stack.clear();
+    }
+
+    pub(crate) fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    // 評価し終えた手の評価値
+    pub(crate) fn evaluations(&self) -> &[((usize, usize), i32)] {
+        &self.evaluations
+    }
+
+    // 現時点での最善手（まだどの手も評価し終えていない場合は最初の有効手）。
+    // すべての手を評価し終えた場合は設定の手のばらつきに従って選ぶ
+    pub(crate) fn best_move(&self) -> Option<(usize, usize)> {
+        let chosen = if self.root_index >= self.root_moves.len() {
+            ai::choose_move(&self.board, self.player, &self.evaluations, &self.config)
+        } else {
+            ai::select_best_move(&self.evaluations)
+        };
+        chosen.or_else(|| self.root_moves.first().copied())
+    }
+
+    // 最大 max_nodes ノードだけ探索を進める。探索が終了した場合は true を返す
+    pub(crate) fn step(&mut self, max_nodes: u32) -> bool {
+        let limit = self.nodes + max_nodes.max(1) as u64;
+
+        while !self.is_finished() && self.nodes < limit {
+            let Some(frame) = self.stack.last_mut() else {
+                self.start_root_move();
+                continue;
+            };
+
+            if frame.next < frame.moves.len() {
+                // 次の子ノードへ
+                let (row, col) = frame.moves[frame.next];
+                frame.next += 1;
+                let mut child = frame.board.clone();
+                child.make_move_on_board(row, col, frame.player);
+                let (depth, alpha, beta, player) = (frame.depth - 1, -frame.beta, -frame.alpha, 3 - frame.player);
+
+                match self.enter(child, depth, alpha, beta, player) {
+                    Entered::Value(eval) => self.apply_to_top(-eval),
+                    Entered::Frame(child_frame) => self.stack.push(child_frame),
+                }
+            } else {
+                // すべての子ノードを調べ終えた
+                let Some(frame) = self.stack.pop() else {
+                    continue;
+                };
+                if self.prunes() {
+                    self.search.store_table(frame.hash, frame.depth, frame.window.0, frame.window.1, frame.best);
+                }
+                // 親ノード（またはルート）の手番の視点に直す
+                let finished = -frame.sign * frame.best;
+                if self.stack.is_empty() {
+                    self.finish_root_move(finished);
+                } else {
+                    self.apply_to_top(finished);
+                }
+            }
+        }
+
+        self.is_finished()
+    }
+
+    // ルートの次の手の評価を開始
+    fn start_root_move(&mut self) {
+        let (row, col) = self.root_moves[self.root_index];
+        match self.strategy {
+            Strategy::Greedy => {
+                self.nodes += 1;
+                let score = ai::calculate_basic_move_score(&self.board, row, col, self.player);
+                self.finish_root_move(score);
+            }
+            Strategy::Minimax(depth) | Strategy::AlphaBeta(depth) => {
+                let mut child = self.board.clone();
+                child.make_move_on_board(row, col, self.player);
+                match self.enter(child, depth.saturating_sub(1), -INFINITY, INFINITY, 3 - self.player) {
+                    Entered::Value(eval) => self.finish_root_move(-eval),
+                    Entered::Frame(frame) => self.stack.push(frame),
+                }
+            }
+        }
+    }
+
+    // ルートの手の評価が確定した
+    fn finish_root_move(&mut self, score: i32) {
+        self.evaluations.push((self.root_moves[self.root_index], score));
+        self.root_index += 1;
+    }
+
+    // ノードに入る（末端ならその場で評価し、パスの場合は相手の手番として入り直す）
+    fn enter(&mut self, board: BoardState, mut depth: u8, mut alpha: i32, mut beta: i32, mut player: i8) -> Entered {
+        let mut sign = 1;
+        loop {
+            self.nodes += 1;
+            if board.is_terminal_state() {
+                return Entered::Value(sign * ai::final_score(&board, player));
+            }
+            if depth == 0 {
+                return Entered::Value(sign * self.search.evaluate_board(&board, player));
+            }
+
+            let mut hash = 0;
+            if self.prunes() {
+                hash = board.hash(player);
+                if let Some(value) = self.search.probe_table(hash, depth, &mut alpha, &mut beta) {
+                    return Entered::Value(sign * value);
+                }
+            }
+
+            let moves = board.get_valid_moves_for_board(player);
+            if moves.is_empty() {
+                // パスして相手のターン
+                depth -= 1;
+                (alpha, beta) = (-beta, -alpha);
+                player = 3 - player;
+                sign = -sign;
+                continue;
+            }
+
+            return Entered::Frame(Frame {
+                board,
+                depth,
+                alpha,
+                beta,
+                player,
+                sign,
+                moves,
+                next: 0,
+                best: -INFINITY,
+                hash,
+                window: (alpha, beta),
+            });
+        }
+    }
+
+    // 枝刈り（と置換表）を使う探索かどうか
+    fn prunes(&self) -> bool {
+        matches!(self.strategy, Strategy::AlphaBeta(_))
+    }
+
+    // 子ノードの評価値（親ノードの手番の視点に直したもの）を親ノードに反映
+    fn apply_to_top(&mut self, eval: i32) {
+        let prune = self.prunes();
+        let Some(frame) = self.stack.last_mut() else {
+            return;
+        };
+
+        frame.best = frame.best.max(eval);
+        frame.alpha = frame.alpha.max(eval);
+
+        if prune && frame.alpha >= frame.beta {
+            frame.next = frame.moves.len(); // アルファベータカット
+        }
+    }
+}
+
+// JavaScript から少しずつ進められる探索
+#[wasm_bindgen]
+pub struct IncrementalSearch {
+    inner: SteppedSearch,
+}
+
+#[wasm_bindgen]
+impl IncrementalSearch {
+    // 局面文字列（OthelloGame.export_position の形式）から探索を作成
+    #[wasm_bindgen(constructor)]
+    pub fn new(position: &str, difficulty: AiDifficulty) -> Result<IncrementalSearch, JsValue> {
+        let (board, player) = BoardState::from_position_string(position).map_err(|e| JsValue::from_str(&e))?;
+        Ok(IncrementalSearch {
+            inner: SteppedSearch::new(board, player, difficulty.into()),
+        })
+    }
+
+    // AiConfig の形式の設定文字列（OthelloGame.ai_config の値）を指定して探索を作成
+    pub fn with_config(position: &str, config: &str) -> Result<IncrementalSearch, JsValue> {
+        let (board, player) = BoardState::from_position_string(position).map_err(|e| JsValue::from_str(&e))?;
+        let config = parse_config(config).map_err(|e| JsValue::from_str(&e))?;
+        Ok(IncrementalSearch {
+            inner: SteppedSearch::new(board, player, config),
+        })
+    }
+
+    // 最大 max_nodes ノードだけ探索を進める。探索が終了した場合は true を返す
+    pub fn step(&mut self, max_nodes: u32) -> bool {
+        self.inner.step(max_nodes)
+    }
+
+    // 現時点での最善手 [行, 列] を取得（有効な手がない場合は [-1, -1]）
+    pub fn best_move(&self) -> Vec<i32> {
+        match self.inner.best_move() {
+            Some((row, col)) => vec![row as i32, col as i32],
+            None => vec![-1, -1],
+        }
+    }
+
+    // 探索を中断（ユーザーが待ったをした場合や新しいゲームを始めた場合など）
+    pub fn cancel(&mut self) {
+        self.inner.cancel();
+    }
+
+    // 探索が終了したかどうか
+    #[wasm_bindgen(getter)]
+    pub fn finished(&self) -> bool {
+        self.inner.is_finished()
+    }
+
+    // 中断されたかどうか
+    #[wasm_bindgen(getter)]
+    pub fn cancelled(&self) -> bool {
+        self.inner.cancelled
+    }
+
+    // これまでに探索したノード数
+    #[wasm_bindgen(getter)]
+    pub fn nodes(&self) -> f64 {
+        self.inner.nodes() as f64
+    }
+
+    // 評価し終えた手の数
+    #[wasm_bindgen(getter)]
+    pub fn completed_moves(&self) -> usize {
+        self.inner.evaluations().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stepped_config_rejects_time_limit() {
+        assert!(parse_config("time=200").is_err());
+        assert!(parse_config("depth=6,time=200").is_err());
+        assert_eq!(parse_config("depth=6").unwrap().strategy, Strategy::AlphaBeta(6));
+    }
+
+    // 少しずつ進めた探索の評価値（手の順に並べたもの）
+    fn stepped_evaluations(position: &str, config: AiConfig) -> Vec<((usize, usize), i32)> {
+        let (board, player) = BoardState::from_position_string(position).unwrap();
+        let mut search = SteppedSearch::new(board, player, config);
+        while !search.step(100) {}
+        let mut evaluations = search.evaluations().to_vec();
+        evaluations.sort();
+        evaluations
+    }
+
+    fn recursive_evaluations(position: &str, config: AiConfig) -> Vec<((usize, usize), i32)> {
+        let (board, player) = BoardState::from_position_string(position).unwrap();
+        let mut evaluations = ai::evaluate_moves(&board, player, &config);
+        evaluations.sort();
+        evaluations
+    }
+
+    #[test]
+    fn stepped_search_matches_recursive_search() {
+        let mut game = crate::game::Game::new();
+        game.play_transcript("f5d6c3d3c4f4").unwrap();
+        let midgame = game.position();
+        // c8 で白がパスする局面（探索の途中でパスをはさむ）
+        let pass = "OOOOOOOX--OOOOOX-OOOOXXX--OXXOXXOOOOOOOX-OXOXOXX-OOXXXXXXO-OXXOO X";
+        for position in [midgame.as_str(), pass] {
+            for strategy in [Strategy::Greedy, Strategy::Minimax(3), Strategy::AlphaBeta(3), Strategy::AlphaBeta(5)] {
+                let config = AiConfig::from(strategy);
+                assert_eq!(stepped_evaluations(position, config), recursive_evaluations(position, config), "{:?}: {}", strategy, position);
+            }
+        }
+    }
+
+    #[test]
+    fn cancel_stops_the_search() {
+        let mut game = crate::game::Game::new();
+        game.play_transcript("f5d6c3d3c4f4").unwrap();
+        let (board, player) = BoardState::from_position_string(&game.position()).unwrap();
+        let mut search = SteppedSearch::new(board, player, AiConfig::from(Strategy::AlphaBeta(5)));
+        assert!(!search.step(50));
+        let (nodes, completed) = (search.nodes(), search.evaluations().len());
+        assert!(completed < search.root_moves.len());
+
+        search.cancel();
+        assert!(search.is_finished());
+        assert!(search.step(1000));
+        assert_eq!((search.nodes(), search.evaluations().len()), (nodes, completed));
+        // 中断しても打てる手は返す
+        assert!(search.best_move().is_some_and(|square| search.root_moves.contains(&square)));
+    }
+}
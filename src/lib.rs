@@ -1,6 +1,36 @@
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
-use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, MouseEvent, Element};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, KeyboardEvent, MouseEvent, PointerEvent, Element};
+
+mod adaptive;
+mod ai;
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod bench;
+mod bitboard;
+mod board;
+mod book;
+pub mod diagram;
+pub mod endgame;
+pub mod explain;
+pub mod game;
+pub mod incremental;
+pub mod perft;
+pub mod review;
+mod rng;
+mod stability;
+mod strength;
+pub mod tournament;
+mod tt;
+pub mod worker;
+
+pub use adaptive::{AdaptiveDifficulty, AdaptiveStrength};
+pub use ai::{AiConfig, AiDifficulty, EvalBreakdown, EvalWeights, Personality, Strategy};
+pub use game::Game;
+pub use strength::Strength;
+use board::BoardState;
+use diagram::{DiagramOptions, MoveRecord};
+use review::ReviewReport;
 
 // パニック時のスタックトレースを有効にする
 #[cfg(feature = "console_error_panic_hook")]
@@ -8,112 +38,72 @@ pub fn set_panic_hook() {
     console_error_panic_hook::set_once();
 }
 
-// AI難易度を表現する列挙型
-#[wasm_bindgen]
-#[derive(Debug, Clone, Copy)]
-pub enum AiDifficulty {
-    Easy = 1,    // 貪欲法
-    Medium = 2,  // ミニマックス 3手先読み
-    Hard = 3,    // ミニマックス + アルファベータ 5手先読み
-    Expert = 4,  // ミニマックス + アルファベータ 7手先読み + 改良評価関数
-}
-
-// ボード状態を表現する構造体（AI計算用）
-#[derive(Clone)]
-struct BoardState {
-    board: [[i8; 8]; 8],
-    current_player: i8,
-}
-
-impl BoardState {
-    // ボード上で手を実行
-    fn make_move_on_board(&mut self, row: usize, col: usize, player: i8) {
-        self.board[row][col] = player;
-
-        let directions = [
-            (-1, -1), (-1, 0), (-1, 1),
-            (0, -1),           (0, 1),
-            (1, -1),  (1, 0),  (1, 1),
-        ];
+// Canvas なしで作成したゲームの盤面の1辺の大きさ（click_at の座標の変換用）
+const HEADLESS_BOARD_SIZE: f64 = 400.0;
 
-        for (dr, dc) in directions.iter() {
-            if self.can_flip_in_direction_board(row, col, *dr, *dc, player) {
-                self.flip_in_direction_board(row, col, *dr, *dc, player);
-            }
-        }
-    }
-
-    // ボード上で指定した方向に石をひっくり返せるかチェック
-    fn can_flip_in_direction_board(&self, row: usize, col: usize, dr: i32, dc: i32, player: i8) -> bool {
-        let mut r = row as i32 + dr;
-        let mut c = col as i32 + dc;
-        let mut found_opponent = false;
-
-        while r >= 0 && r < 8 && c >= 0 && c < 8 {
-            let current_piece = self.board[r as usize][c as usize];
-            
-            if current_piece == 0 {
-                return false;
-            } else if current_piece == player {
-                return found_opponent;
-            } else {
-                found_opponent = true;
-            }
-            
-            r += dr;
-            c += dc;
-        }
-
-        false
-    }
-
-    // ボード上で指定した方向の石をひっくり返す
-    fn flip_in_direction_board(&mut self, row: usize, col: usize, dr: i32, dc: i32, player: i8) {
-        let mut r = row as i32 + dr;
-        let mut c = col as i32 + dc;
-
-        while r >= 0 && r < 8 && c >= 0 && c < 8 {
-            let current_piece = self.board[r as usize][c as usize];
-            
-            if current_piece == player {
-                break;
-            } else {
-                self.board[r as usize][c as usize] = player;
-            }
-            
-            r += dr;
-            c += dc;
-        }
-    }
-}
+// ヒートマップの探索の深さの上限（着手のたびにメインスレッドで評価するため、上級以上でも描画を止めない深さにする）
+const HEATMAP_DEPTH: u8 = 3;
 
 // オセロのゲーム状態を表現する構造体
 #[wasm_bindgen]
 pub struct OthelloGame {
     board: [[i8; 8]; 8], // 0: 空, 1: 黒, 2: 白
     current_player: i8,  // 1: 黒, 2: 白
-    canvas: HtmlCanvasElement,
-    context: CanvasRenderingContext2d,
+    canvas: Option<(HtmlCanvasElement, CanvasRenderingContext2d)>, // 描画先の Canvas と 2D コンテキスト（None の場合は描画しない）
     ai_difficulty: AiDifficulty, // AI難易度
+    ai_personality: Personality, // AIの個性
+    ai_strength: Option<Strength>, // 指定した場合は難易度の代わりに使う強さ（強さの自動調整用）
+    ai_variety: bool,            // AIの手にばらつきを持たせるか
+    ai_seed: u32,                // 手のばらつきに使う乱数のシード
+    history: Vec<MoveRecord>,    // 着手履歴（行, 列, 手番）
+    hover: Option<(usize, usize)>,  // ポインタが乗っているマス
+    cursor: Option<(usize, usize)>, // キーボード操作用のカーソル位置
+    view_rotation: u8,              // 表示の回転（時計回りに90度単位、0〜3）
+    view_mirrored: bool,            // 表示を左右反転するか
+    heatmap_enabled: bool,          // 評価値ヒートマップを表示するか
+    heatmap: Vec<((usize, usize), i32)>, // ヒートマップ用の各有効手の評価値
+    stable_marks_enabled: bool,     // 確定石に印を付けるか
 }
 
 #[wasm_bindgen]
 impl OthelloGame {
     #[wasm_bindgen(constructor)]
     pub fn new(canvas: HtmlCanvasElement) -> Result<OthelloGame, JsValue> {
-        set_panic_hook();
-        
         let context = canvas
             .get_context("2d")?
             .unwrap()
             .dyn_into::<CanvasRenderingContext2d>()?;
 
+        let game = OthelloGame::create(Some((canvas, context)));
+        game.draw_board()?;
+        Ok(game)
+    }
+
+    // Canvas なしでゲームを作成（描画は行わない。Node.js でのテストや画面を持たない利用向け）
+    pub fn headless() -> OthelloGame {
+        OthelloGame::create(None)
+    }
+
+    fn create(canvas: Option<(HtmlCanvasElement, CanvasRenderingContext2d)>) -> OthelloGame {
+        set_panic_hook();
+
         let mut game = OthelloGame {
             board: [[0; 8]; 8],
             current_player: 1, // 黒から開始
             canvas,
-            context,
             ai_difficulty: AiDifficulty::Medium, // デフォルトは中級
+            ai_personality: Personality::Balanced,
+            ai_strength: None,
+            ai_variety: false,
+            ai_seed: 0,
+            history: Vec::new(),
+            hover: None,
+            cursor: None,
+            view_rotation: 0,
+            view_mirrored: false,
+            heatmap_enabled: false,
+            heatmap: Vec::new(),
+            stable_marks_enabled: false,
         };
 
         // 初期配置を設定
@@ -121,59 +111,143 @@ impl OthelloGame {
         game.board[3][4] = 1; // 黒
         game.board[4][3] = 1; // 黒
         game.board[4][4] = 2; // 白
-
-        game.draw_board()?;
-        Ok(game)
+        game
     }
 
     // ボードを描画する
     pub fn draw_board(&self) -> Result<(), JsValue> {
-        let canvas_width = self.canvas.width() as f64;
-        let canvas_height = self.canvas.height() as f64;
-        let size = canvas_width.min(canvas_height);
+        let Some((_, context)) = &self.canvas else {
+            return Ok(());
+        };
+        let size = self.board_size();
         let cell_size = size / 8.0;
         
         // ボードの背景を緑で塗りつぶし
-        self.context.set_fill_style(&"#228B22".into());
-        self.context.fill_rect(0.0, 0.0, size, size);
+        context.set_fill_style_str("#228B22");
+        context.fill_rect(0.0, 0.0, size, size);
 
         // グリッドを描画
-        self.context.set_stroke_style(&"#000".into());
-        self.context.set_line_width(2.0);
+        context.set_stroke_style_str("#000");
+        context.set_line_width(2.0);
         
         for i in 0..=8 {
             let pos = i as f64 * cell_size;
-            self.context.begin_path();
-            self.context.move_to(pos, 0.0);
-            self.context.line_to(pos, size);
-            self.context.stroke();
+            context.begin_path();
+            context.move_to(pos, 0.0);
+            context.line_to(pos, size);
+            context.stroke();
             
-            self.context.begin_path();
-            self.context.move_to(0.0, pos);
-            self.context.line_to(size, pos);
-            self.context.stroke();
+            context.begin_path();
+            context.move_to(0.0, pos);
+            context.line_to(size, pos);
+            context.stroke();
+        }
+
+        // 評価値ヒートマップ（最善手ほど青、最悪手ほど赤）
+        if self.heatmap_enabled && !self.heatmap.is_empty() {
+            let best = self.heatmap.iter().map(|&(_, score)| score).max().unwrap_or(0);
+            let worst = self.heatmap.iter().map(|&(_, score)| score).min().unwrap_or(0);
+
+            context.set_text_align("center");
+            context.set_text_baseline("middle");
+            context.set_font(&format!("bold {}px sans-serif", (cell_size * 0.28).round()));
+
+            for &((row, col), score) in &self.heatmap {
+                let ratio = if best > worst {
+                    (score as f64 - worst as f64) / (best as f64 - worst as f64)
+                } else {
+                    1.0
+                };
+                let (x, y) = self.cell_origin(row, col, cell_size);
+
+                context.set_fill_style_str(&format!("hsla({:.0}, 90%, 50%, 0.6)", ratio * 220.0));
+                context.fill_rect(x + 1.0, y + 1.0, cell_size - 2.0, cell_size - 2.0);
+
+                context.set_fill_style_str("#FFF");
+                context.fill_text(&score.to_string(), x + cell_size / 2.0, y + cell_size / 2.0)?;
+            }
+        }
+
+        // ポインタが乗っている有効なマスを強調表示
+        if let Some((row, col)) = self.hover {
+            if self.is_valid_move(row, col) {
+                context.set_fill_style_str("rgba(255, 255, 255, 0.3)");
+                let (x, y) = self.cell_origin(row, col, cell_size);
+                context.fill_rect(x + 1.0, y + 1.0, cell_size - 2.0, cell_size - 2.0);
+            }
+        }
+
+        // キーボード操作用のカーソルを描画
+        if let Some((row, col)) = self.cursor {
+            context.set_stroke_style_str("#FFD700");
+            context.set_line_width(4.0);
+            let (x, y) = self.cell_origin(row, col, cell_size);
+            context.stroke_rect(x + 3.0, y + 3.0, cell_size - 6.0, cell_size - 6.0);
+            context.set_line_width(2.0);
         }
 
         // 石を描画
         for row in 0..8 {
             for col in 0..8 {
                 if self.board[row][col] != 0 {
-                    let x = col as f64 * cell_size + cell_size / 2.0;
-                    let y = row as f64 * cell_size + cell_size / 2.0;
+                    let (left, top) = self.cell_origin(row, col, cell_size);
+                    let x = left + cell_size / 2.0;
+                    let y = top + cell_size / 2.0;
                     let radius = (cell_size * 0.4).min(25.0); // セルサイズに応じて調整、最大25px
                     
-                    self.context.begin_path();
-                    self.context.arc(x, y, radius, 0.0, 2.0 * std::f64::consts::PI)?;
+                    context.begin_path();
+                    context.arc(x, y, radius, 0.0, 2.0 * std::f64::consts::PI)?;
                     
                     if self.board[row][col] == 1 {
-                        self.context.set_fill_style(&"#000".into());
+                        context.set_fill_style_str("#000");
                     } else {
-                        self.context.set_fill_style(&"#FFF".into());
+                        context.set_fill_style_str("#FFF");
                     }
-                    self.context.fill();
+                    context.fill();
                     
-                    self.context.set_stroke_style(&"#000".into());
-                    self.context.stroke();
+                    context.set_stroke_style_str("#000");
+                    context.stroke();
+                }
+            }
+        }
+
+        // 確定石の印（石の中央に小さな金色の四角）
+        if self.stable_marks_enabled {
+            let board = self.clone_board();
+            let mark = cell_size * 0.14;
+            context.set_fill_style_str("#FFD700");
+            for (row, col) in stability::stable_squares(&board, 1).into_iter().chain(stability::stable_squares(&board, 2)) {
+                let (left, top) = self.cell_origin(row, col, cell_size);
+                context.fill_rect(left + (cell_size - mark) / 2.0, top + (cell_size - mark) / 2.0, mark, mark);
+            }
+        }
+
+        // 着手プレビュー（半透明の石と、ひっくり返る石の印）
+        if let Some((row, col)) = self.hover.or(self.cursor) {
+            let flips = self.clone_board().get_flips(row, col, self.current_player);
+            if !flips.is_empty() {
+                let radius = (cell_size * 0.4).min(25.0);
+                let (left, top) = self.cell_origin(row, col, cell_size);
+                let x = left + cell_size / 2.0;
+                let y = top + cell_size / 2.0;
+
+                context.begin_path();
+                context.arc(x, y, radius, 0.0, 2.0 * std::f64::consts::PI)?;
+                if self.current_player == 1 {
+                    context.set_fill_style_str("rgba(0, 0, 0, 0.5)");
+                } else {
+                    context.set_fill_style_str("rgba(255, 255, 255, 0.6)");
+                }
+                context.fill();
+
+                context.set_fill_style_str("#FF4500");
+                for &(flip_row, flip_col) in &flips {
+                    let (left, top) = self.cell_origin(flip_row, flip_col, cell_size);
+                    let fx = left + cell_size / 2.0;
+                    let fy = top + cell_size / 2.0;
+                    context.begin_path();
+                    context.arc(fx, fy, radius * 0.3, 0.0, 2.0 * std::f64::consts::PI)?;
+                    context.fill();
                 }
             }
         }
@@ -183,25 +257,188 @@ impl OthelloGame {
 
     // クリック処理
     pub fn handle_click(&mut self, event: MouseEvent) -> Result<(), JsValue> {
-        let rect = self.canvas.dyn_ref::<Element>().unwrap().get_bounding_client_rect();
-        let x = event.client_x() as f64 - rect.left();
-        let y = event.client_y() as f64 - rect.top();
-        
-        let canvas_width = self.canvas.width() as f64;
-        let canvas_height = self.canvas.height() as f64;
-        let size = canvas_width.min(canvas_height);
-        let cell_size = size / 8.0;
+        if let Some((row, col)) = self.square_at(event.client_x() as f64, event.client_y() as f64) {
+            self.play_at(row, col)?;
+        }
         
+        Ok(())
+    }
+
+    // Canvas 上の座標（Canvas の解像度の単位。Canvas がない場合は1辺 HEADLESS_BOARD_SIZE の盤面）をクリックしたものとして石を置く。置けた場合は true を返す
+    pub fn click_at(&mut self, x: f64, y: f64) -> Result<bool, JsValue> {
+        match self.square_at_point(x, y) {
+            Some((row, col)) => self.play_at(row, col),
+            None => Ok(false),
+        }
+    }
+
+    // ポインタ（マウス・タッチ・ペン）イベント処理。石を置いた場合は true を返す
+    pub fn handle_pointer(&mut self, event: PointerEvent) -> Result<bool, JsValue> {
+        let square = self.square_at(event.client_x() as f64, event.client_y() as f64);
+        self.pointer_on_square(&event.type_(), &event.pointer_type(), square)
+    }
+
+    // Canvas 上の座標（click_at と同じ単位）でのポインタイベント（event_type は pointermove などのイベント名）。石を置いた場合は true を返す
+    pub fn pointer_at(&mut self, event_type: &str, pointer_type: &str, x: f64, y: f64) -> Result<bool, JsValue> {
+        let square = self.square_at_point(x, y);
+        self.pointer_on_square(event_type, pointer_type, square)
+    }
+
+    fn pointer_on_square(&mut self, event_type: &str, pointer_type: &str, square: Option<(usize, usize)>) -> Result<bool, JsValue> {
+        match event_type {
+            "pointermove" => {
+                // タッチ操作ではホバーが存在しないため、プレビューはマウスとペンのみ
+                if pointer_type != "touch" && square != self.hover {
+                    self.hover = square;
+                    self.draw_board()?;
+                }
+                Ok(false)
+            }
+            "pointerleave" | "pointercancel" => {
+                if self.hover.is_some() {
+                    self.hover = None;
+                    self.draw_board()?;
+                }
+                Ok(false)
+            }
+            "pointerup" => {
+                // ポインタ操作に切り替わったらキーボードカーソルは隠す
+                self.cursor = None;
+                match square {
+                    Some((row, col)) => self.play_at(row, col),
+                    None => Ok(false),
+                }
+            }
+            _ => Ok(false),
+        }
+    }
+
+    // キーボードイベント処理（矢印キーでカーソル移動、Enter / Space で着手）。石を置いた場合は true を返す
+    pub fn handle_key(&mut self, event: KeyboardEvent) -> Result<bool, JsValue> {
+        let key = event.key();
+        if matches!(key.as_str(), "ArrowUp" | "ArrowDown" | "ArrowLeft" | "ArrowRight" | "Enter" | " ") {
+            event.prevent_default();
+        }
+        self.press_key(&key)
+    }
+
+    // キー入力（KeyboardEvent.key の値）を処理する。石を置いた場合は true を返す
+    pub fn press_key(&mut self, key: &str) -> Result<bool, JsValue> {
+        if !matches!(key, "ArrowUp" | "ArrowDown" | "ArrowLeft" | "ArrowRight" | "Enter" | " ") {
+            return Ok(false);
+        }
+
+        // 最初のキー入力ではカーソルを最初の有効手に表示するだけにする
+        let Some((row, col)) = self.cursor else {
+            self.cursor = Some(self.get_valid_moves().first().copied().unwrap_or((3, 3)));
+            self.draw_board()?;
+            return Ok(false);
+        };
+
+        // 矢印キーは表示上の向きで解釈する
+        let (view_row, view_col) = self.board_to_view(row, col);
+        let (next_row, next_col) = match key {
+            "ArrowUp" => (view_row.saturating_sub(1), view_col),
+            "ArrowDown" => ((view_row + 1).min(7), view_col),
+            "ArrowLeft" => (view_row, view_col.saturating_sub(1)),
+            "ArrowRight" => (view_row, (view_col + 1).min(7)),
+            _ => return self.play_at(row, col),
+        };
+        self.cursor = Some(self.view_to_board(next_row, next_col));
+        self.draw_board()?;
+        Ok(false)
+    }
+
+    // キーボードカーソルの位置（[行, 列]。表示していない場合は空）
+    #[wasm_bindgen(getter)]
+    pub fn cursor(&self) -> Vec<i32> {
+        self.cursor.map_or_else(Vec::new, |(row, col)| vec![row as i32, col as i32])
+    }
+
+    // ポインタが乗っているマス（[行, 列]。乗っていない場合は空）
+    #[wasm_bindgen(getter)]
+    pub fn hover(&self) -> Vec<i32> {
+        self.hover.map_or_else(Vec::new, |(row, col)| vec![row as i32, col as i32])
+    }
+
+    // 画面上の座標（clientX, clientY）をマスに変換
+    fn square_at(&self, client_x: f64, client_y: f64) -> Option<(usize, usize)> {
+        let (canvas, _) = self.canvas.as_ref()?;
+        let rect = canvas.dyn_ref::<Element>()?.get_bounding_client_rect();
+        if rect.width() <= 0.0 || rect.height() <= 0.0 {
+            return None;
+        }
+
+        // CSS上の表示サイズとCanvasの解像度が異なる場合に備えて拡大率を補正
+        let x = (client_x - rect.left()) * canvas.width() as f64 / rect.width();
+        let y = (client_y - rect.top()) * canvas.height() as f64 / rect.height();
+        self.square_at_point(x, y)
+    }
+
+    // Canvas 上の座標（Canvas の解像度の単位）をマスに変換
+    fn square_at_point(&self, x: f64, y: f64) -> Option<(usize, usize)> {
+        if x < 0.0 || y < 0.0 {
+            return None;
+        }
+
+        let cell_size = self.board_size() / 8.0;
         let col = (x / cell_size) as usize;
         let row = (y / cell_size) as usize;
-        
-        if row < 8 && col < 8 && self.is_valid_move(row, col) {
-            self.make_move(row, col);
-            self.current_player = if self.current_player == 1 { 2 } else { 1 };
-            self.draw_board()?;
+
+        if row < 8 && col < 8 {
+            Some(self.view_to_board(row, col))
+        } else {
+            None
+        }
+    }
+
+    // 盤面の1辺の大きさ（Canvas がない場合は HEADLESS_BOARD_SIZE）
+    fn board_size(&self) -> f64 {
+        match &self.canvas {
+            Some((canvas, _)) => (canvas.width() as f64).min(canvas.height() as f64),
+            None => HEADLESS_BOARD_SIZE,
         }
-        
-        Ok(())
+    }
+
+    // 盤面上のマスを表示上のマスに変換（左右反転してから時計回りに回転）
+    fn board_to_view(&self, row: usize, col: usize) -> (usize, usize) {
+        let (mut r, mut c) = if self.view_mirrored { (row, 7 - col) } else { (row, col) };
+        for _ in 0..self.view_rotation {
+            (r, c) = (c, 7 - r);
+        }
+        (r, c)
+    }
+
+    // 表示上のマスを盤面上のマスに変換（board_to_view の逆変換）
+    fn view_to_board(&self, row: usize, col: usize) -> (usize, usize) {
+        let (mut r, mut c) = (row, col);
+        for _ in 0..self.view_rotation {
+            (r, c) = (7 - c, r);
+        }
+        if self.view_mirrored {
+            (r, 7 - c)
+        } else {
+            (r, c)
+        }
+    }
+
+    // 盤面上のマスの描画位置（左上の座標）を取得
+    fn cell_origin(&self, row: usize, col: usize, cell_size: f64) -> (f64, f64) {
+        let (view_row, view_col) = self.board_to_view(row, col);
+        (view_col as f64 * cell_size, view_row as f64 * cell_size)
+    }
+
+    // 指定したマスに現在のプレイヤーの石を置く。置けた場合は true を返す
+    fn play_at(&mut self, row: usize, col: usize) -> Result<bool, JsValue> {
+        if !self.is_valid_move(row, col) {
+            return Ok(false);
+        }
+
+        self.make_move(row, col);
+        self.current_player = if self.current_player == 1 { 2 } else { 1 };
+        self.refresh_heatmap();
+        self.draw_board()?;
+        Ok(true)
     }
 
     // 有効な手かどうかをチェック
@@ -231,7 +468,7 @@ impl OthelloGame {
         let mut c = col as i32 + dc;
         let mut found_opponent = false;
 
-        while r >= 0 && r < 8 && c >= 0 && c < 8 {
+        while (0..8).contains(&r) && (0..8).contains(&c) {
             let current_piece = self.board[r as usize][c as usize];
             
             if current_piece == 0 {
@@ -252,6 +489,7 @@ impl OthelloGame {
     // 手を実行
     fn make_move(&mut self, row: usize, col: usize) {
         self.board[row][col] = self.current_player;
+        self.history.push((row, col, self.current_player));
 
         let directions = [
             (-1, -1), (-1, 0), (-1, 1),
@@ -271,7 +509,7 @@ impl OthelloGame {
         let mut r = row as i32 + dr;
         let mut c = col as i32 + dc;
 
-        while r >= 0 && r < 8 && c >= 0 && c < 8 {
+        while (0..8).contains(&r) && (0..8).contains(&c) {
             let current_piece = self.board[r as usize][c as usize];
             
             if current_piece == self.current_player {
@@ -285,6 +523,78 @@ impl OthelloGame {
         }
     }
 
+    // 現在の局面をSVG図として出力（highlights は [行, 列, 行, 列, ...] の形式）
+    pub fn export_svg(&self, show_move_numbers: bool, highlights: Vec<i32>) -> String {
+        let options = DiagramOptions {
+            show_move_numbers,
+            highlights: highlights
+                .chunks_exact(2)
+                .filter(|square| (0..8).contains(&square[0]) && (0..8).contains(&square[1]))
+                .map(|square| (square[0] as usize, square[1] as usize))
+                .collect(),
+            ..DiagramOptions::default()
+        };
+        diagram::render_svg(&self.board, &self.history, &options)
+    }
+
+    // 指定したマスに置いた場合にひっくり返る石を取得（[行, 列, 行, 列, ...] の形式）
+    pub fn get_flip_preview(&self, row: usize, col: usize) -> Vec<i32> {
+        if row >= 8 || col >= 8 {
+            return Vec::new();
+        }
+
+        self.clone_board()
+            .get_flips(row, col, self.current_player)
+            .iter()
+            .flat_map(|&(r, c)| [r as i32, c as i32])
+            .collect()
+    }
+
+    // 着手履歴を取得（[行, 列, 手番, 行, 列, 手番, ...] の形式）
+    pub fn get_move_history(&self) -> Vec<i32> {
+        self.history
+            .iter()
+            .flat_map(|&(row, col, player)| [row as i32, col as i32, player as i32])
+            .collect()
+    }
+
+    // 表示の回転を設定（時計回りに90度単位。1: 90度, 2: 180度, 3: 270度）
+    pub fn set_view_rotation(&mut self, quarter_turns: u8) -> Result<(), JsValue> {
+        self.view_rotation = quarter_turns % 4;
+        self.draw_board()
+    }
+
+    // 表示の回転を取得
+    #[wasm_bindgen(getter)]
+    pub fn view_rotation(&self) -> u8 {
+        self.view_rotation
+    }
+
+    // 表示の左右反転を設定
+    pub fn set_view_mirrored(&mut self, mirrored: bool) -> Result<(), JsValue> {
+        self.view_mirrored = mirrored;
+        self.draw_board()
+    }
+
+    // 表示の左右反転を取得
+    #[wasm_bindgen(getter)]
+    pub fn view_mirrored(&self) -> bool {
+        self.view_mirrored
+    }
+
+    // 現在の局面を文字列として出力（Web Worker での探索用。64マス分の X / O / - と手番）
+    pub fn export_position(&self) -> String {
+        self.clone_board().to_position_string(self.current_player)
+    }
+
+    // 指定したマスに現在のプレイヤーの石を置く（Web Worker から返された手の適用用）。置けた場合は true を返す
+    pub fn play_move(&mut self, row: usize, col: usize) -> Result<bool, JsValue> {
+        if row >= 8 || col >= 8 {
+            return Ok(false);
+        }
+        self.play_at(row, col)
+    }
+
     // 現在のプレイヤーを取得
     #[wasm_bindgen(getter)]
     pub fn current_player(&self) -> i8 {
@@ -294,6 +604,50 @@ impl OthelloGame {
     // AI難易度を設定
     pub fn set_ai_difficulty(&mut self, difficulty: AiDifficulty) {
         self.ai_difficulty = difficulty;
+        self.refresh_heatmap();
+    }
+
+    // 評価値ヒートマップの表示を切り替え
+    pub fn set_heatmap_enabled(&mut self, enabled: bool) -> Result<(), JsValue> {
+        if self.heatmap_enabled != enabled {
+            self.heatmap_enabled = enabled;
+            self.refresh_heatmap();
+        }
+        self.draw_board()
+    }
+
+    // 評価値ヒートマップの表示状態を取得
+    #[wasm_bindgen(getter)]
+    pub fn heatmap_enabled(&self) -> bool {
+        self.heatmap_enabled
+    }
+
+    // ヒートマップの各有効手の評価値を取得（[行, 列, 評価値, 行, 列, 評価値, ...] の形式。表示していない場合は空）
+    pub fn get_heatmap(&self) -> Vec<i32> {
+        self.heatmap
+            .iter()
+            .flat_map(|&((row, col), score)| [row as i32, col as i32, score])
+            .collect()
+    }
+
+    // 確定石の印の表示を切り替え
+    pub fn set_stable_marks_enabled(&mut self, enabled: bool) -> Result<(), JsValue> {
+        self.stable_marks_enabled = enabled;
+        self.draw_board()
+    }
+
+    // 確定石の印の表示状態を取得
+    #[wasm_bindgen(getter)]
+    pub fn stable_marks_enabled(&self) -> bool {
+        self.stable_marks_enabled
+    }
+
+    // player の確定石を取得（[行, 列, 行, 列, ...] の形式）
+    pub fn get_stable_discs(&self, player: i8) -> Vec<i32> {
+        stability::stable_squares(&self.clone_board(), player)
+            .iter()
+            .flat_map(|&(row, col)| [row as i32, col as i32])
+            .collect()
     }
 
     // AI難易度を取得
@@ -304,14 +658,54 @@ impl OthelloGame {
 
     // AI難易度の説明を取得
     pub fn get_ai_difficulty_description(&self) -> String {
-        match self.ai_difficulty {
-            AiDifficulty::Easy => "初級 (貪欲法)".to_string(),
-            AiDifficulty::Medium => "中級 (ミニマックス 3手先読み)".to_string(),
-            AiDifficulty::Hard => "上級 (アルファベータ 5手先読み)".to_string(),
-            AiDifficulty::Expert => "エキスパート (アルファベータ 7手先読み + 高度評価)".to_string(),
+        match self.ai_strength {
+            Some(strength) => format!("強さ {:.0} (推定レーティング {:.0})", strength.value(), strength.elo()),
+            None => self.ai_difficulty.description().to_string(),
         }
     }
 
+    // AIの強さを 0〜100 で設定（難易度の代わりに使う。AdaptiveDifficulty.strength の値を渡す）
+    pub fn set_ai_strength(&mut self, strength: f64) {
+        self.ai_strength = Some(Strength::new(strength));
+        self.refresh_heatmap();
+    }
+
+    // set_ai_strength の指定を解除して難易度に戻す
+    pub fn clear_ai_strength(&mut self) {
+        self.ai_strength = None;
+        self.refresh_heatmap();
+    }
+
+    // AIの個性を設定
+    pub fn set_ai_personality(&mut self, personality: Personality) {
+        self.ai_personality = personality;
+        self.refresh_heatmap();
+    }
+
+    // AIの個性を取得
+    #[wasm_bindgen(getter)]
+    pub fn ai_personality(&self) -> Personality {
+        self.ai_personality
+    }
+
+    // AIの手のばらつき（定石と最善手に近い手からの無作為な選択）を切り替え。seed が同じなら同じ手順になる
+    pub fn set_ai_variety(&mut self, enabled: bool, seed: u32) {
+        self.ai_variety = enabled;
+        self.ai_seed = seed;
+    }
+
+    // AIの手のばらつきが有効かどうか
+    #[wasm_bindgen(getter)]
+    pub fn ai_variety(&self) -> bool {
+        self.ai_variety
+    }
+
+    // 現在のAIの設定を AiConfig の形式の文字列で取得（search_position_with_config や IncrementalSearch.with_config に渡す）
+    #[wasm_bindgen(getter)]
+    pub fn ai_config(&self) -> String {
+        self.config().to_string()
+    }
+
     // スコアを取得
     pub fn get_score(&self) -> Vec<i32> {
         let mut black_count = 0;
@@ -358,189 +752,90 @@ impl OthelloGame {
 
     // AI の手を取得（難易度に応じた戦略）
     pub fn get_ai_move(&self) -> Vec<i32> {
-        match self.ai_difficulty {
-            AiDifficulty::Easy => self.get_greedy_move(),
-            AiDifficulty::Medium => self.get_minimax_move(3),
-            AiDifficulty::Hard => self.get_alpha_beta_move(5),
-            AiDifficulty::Expert => self.get_alpha_beta_move(7),
+        match ai::choose_move(&self.clone_board(), self.current_player, &self.evaluate_moves(), &self.config()) {
+            Some((row, col)) => vec![row as i32, col as i32],
+            None => vec![-1, -1],
         }
     }
 
-    // 貪欲法（簡単）
-    fn get_greedy_move(&self) -> Vec<i32> {
-        let valid_moves = self.get_valid_moves();
-        if valid_moves.is_empty() {
-            return vec![-1, -1];
-        }
-
-        let mut best_move = valid_moves[0];
-        let mut best_score = self.calculate_basic_move_score(best_move.0, best_move.1);
-
-        for &(row, col) in &valid_moves {
-            let score = self.calculate_basic_move_score(row, col);
-            if score > best_score {
-                best_score = score;
-                best_move = (row, col);
-            }
-        }
-
-        vec![best_move.0 as i32, best_move.1 as i32]
+    // 各有効手のAI評価値を取得（[行, 列, 評価値, 行, 列, 評価値, ...] の形式）
+    pub fn get_move_evaluations(&self) -> Vec<i32> {
+        self.evaluate_moves()
+            .iter()
+            .flat_map(|&((row, col), score)| [row as i32, col as i32, score])
+            .collect()
     }
 
-    // ミニマックス法
-    fn get_minimax_move(&self, depth: u8) -> Vec<i32> {
-        let valid_moves = self.get_valid_moves();
-        if valid_moves.is_empty() {
-            return vec![-1, -1];
-        }
-
-        let mut best_move = valid_moves[0];
-        let mut best_score = i32::MIN;
-
-        for &(row, col) in &valid_moves {
-            let mut game_copy = self.clone_board();
-            game_copy.make_move_on_board(row, col, self.current_player);
-            let score = self.minimax(&game_copy, depth - 1, false, 3 - self.current_player);
-            
-            if score > best_score {
-                best_score = score;
-                best_move = (row, col);
-            }
-        }
-
-        vec![best_move.0 as i32, best_move.1 as i32]
+    // 難易度と個性に応じた設定で各有効手を評価
+    fn evaluate_moves(&self) -> Vec<((usize, usize), i32)> {
+        ai::evaluate_moves(&self.clone_board(), self.current_player, &self.config())
     }
 
-    // アルファベータ法
-    fn get_alpha_beta_move(&self, depth: u8) -> Vec<i32> {
-        let valid_moves = self.get_valid_moves();
-        if valid_moves.is_empty() {
-            return vec![-1, -1];
+    // 難易度（または強さ）・個性・手のばらつきを合わせたAIの設定
+    fn config(&self) -> AiConfig {
+        let base = match self.ai_strength {
+            Some(strength) => strength.config(),
+            None => self.ai_difficulty.into(),
+        };
+        let config = AiConfig {
+            weights: self.ai_personality.weights(),
+            ..base
+        };
+        if self.ai_variety {
+            config.with_variety(self.ai_seed as u64)
+        } else {
+            config
         }
+    }
 
-        let mut best_move = valid_moves[0];
-        let mut best_score = i32::MIN;
-
-        for &(row, col) in &valid_moves {
-            let mut game_copy = self.clone_board();
-            game_copy.make_move_on_board(row, col, self.current_player);
-            let score = self.alpha_beta(&game_copy, depth - 1, i32::MIN, i32::MAX, false, 3 - self.current_player);
-            
-            if score > best_score {
-                best_score = score;
-                best_move = (row, col);
-            }
+    // ヒートマップ用の評価値を再計算（表示中のみ。深さは HEATMAP_DEPTH まで）
+    fn refresh_heatmap(&mut self) {
+        if !self.heatmap_enabled {
+            self.heatmap = Vec::new();
+            return;
         }
-
-        vec![best_move.0 as i32, best_move.1 as i32]
+        let config = self.config();
+        let strategy = match config.strategy {
+            Strategy::Greedy => Strategy::Greedy,
+            Strategy::Minimax(depth) => Strategy::Minimax(depth.min(HEATMAP_DEPTH)),
+            Strategy::AlphaBeta(depth) => Strategy::AlphaBeta(depth.min(HEATMAP_DEPTH)),
+        };
+        self.heatmap = ai::evaluate_moves(&self.clone_board(), self.current_player, &AiConfig { strategy, ..config });
     }
 
     // ボードをクローンして新しいゲーム状態を作成
     fn clone_board(&self) -> BoardState {
         BoardState {
             board: self.board,
-            current_player: self.current_player,
         }
     }
 
-    // ミニマックス法の実装
-    fn minimax(&self, board: &BoardState, depth: u8, maximizing: bool, player: i8) -> i32 {
-        if depth == 0 || self.is_terminal_state(board) {
-            return self.evaluate_board(board);
-        }
-
-        let valid_moves = self.get_valid_moves_for_board(board, player);
-        
-        if valid_moves.is_empty() {
-            // パスして相手のターン
-            return self.minimax(board, depth - 1, !maximizing, 3 - player);
-        }
-
-        if maximizing {
-            let mut max_eval = i32::MIN;
-            for &(row, col) in &valid_moves {
-                let mut new_board = board.clone();
-                new_board.make_move_on_board(row, col, player);
-                let eval = self.minimax(&new_board, depth - 1, false, 3 - player);
-                max_eval = max_eval.max(eval);
-            }
-            max_eval
-        } else {
-            let mut min_eval = i32::MAX;
-            for &(row, col) in &valid_moves {
-                let mut new_board = board.clone();
-                new_board.make_move_on_board(row, col, player);
-                let eval = self.minimax(&new_board, depth - 1, true, 3 - player);
-                min_eval = min_eval.min(eval);
-            }
-            min_eval
-        }
+    // 現在の局面で (row, col) に打つ手の説明文（language は "ja" または "en"、AI の評価関数の重みで説明する）。AI の手を打つ前に呼ぶ
+    pub fn explain_move(&self, row: usize, col: usize, language: &str) -> Result<String, JsValue> {
+        let game = Game::from_position(&self.export_position()).map_err(|e| JsValue::from_str(&e))?;
+        let language = language.parse().map_err(|e: String| JsValue::from_str(&e))?;
+        game.explain_move_with(row, col, language, self.config().weights).ok_or_else(|| JsValue::from_str("有効な手ではありません"))
     }
 
-    // アルファベータ法の実装
-    fn alpha_beta(&self, board: &BoardState, depth: u8, mut alpha: i32, mut beta: i32, maximizing: bool, player: i8) -> i32 {
-        if depth == 0 || self.is_terminal_state(board) {
-            return self.evaluate_board(board);
-        }
-
-        let valid_moves = self.get_valid_moves_for_board(board, player);
-        
-        if valid_moves.is_empty() {
-            // パスして相手のターン
-            return self.alpha_beta(board, depth - 1, alpha, beta, !maximizing, 3 - player);
-        }
-
-        if maximizing {
-            let mut max_eval = i32::MIN;
-            for &(row, col) in &valid_moves {
-                let mut new_board = board.clone();
-                new_board.make_move_on_board(row, col, player);
-                let eval = self.alpha_beta(&new_board, depth - 1, alpha, beta, false, 3 - player);
-                max_eval = max_eval.max(eval);
-                alpha = alpha.max(eval);
-                if beta <= alpha {
-                    break; // アルファベータカット
-                }
-            }
-            max_eval
-        } else {
-            let mut min_eval = i32::MAX;
-            for &(row, col) in &valid_moves {
-                let mut new_board = board.clone();
-                new_board.make_move_on_board(row, col, player);
-                let eval = self.alpha_beta(&new_board, depth - 1, alpha, beta, true, 3 - player);
-                min_eval = min_eval.min(eval);
-                beta = beta.min(eval);
-                if beta <= alpha {
-                    break; // アルファベータカット
-                }
-            }
-            min_eval
-        }
+    // 現在の局面の評価値の内訳（AI の評価関数の重み、手番のプレイヤーの視点。1行に1項目）
+    pub fn get_evaluation_breakdown(&self) -> Result<String, JsValue> {
+        let game = Game::from_position(&self.export_position()).map_err(|e| JsValue::from_str(&e))?;
+        Ok(game.evaluation_breakdown_with(self.current_player, self.config().weights).to_string())
     }
 
-    // 特定方向でひっくり返せる石の数を数える
-    fn count_flips_in_direction(&self, row: usize, col: usize, dr: i32, dc: i32) -> i32 {
-        let mut r = row as i32 + dr;
-        let mut c = col as i32 + dc;
-        let mut count = 0;
+    // 初期配置からの棋譜を "f5d6c3..." の形式で取得（review_transcript に渡す。パスは省略される）
+    pub fn get_transcript(&self) -> String {
+        self.history.iter().map(|&(row, col, _)| game::square_name(row, col)).collect()
+    }
 
-        while r >= 0 && r < 8 && c >= 0 && c < 8 {
-            let current_piece = self.board[r as usize][c as usize];
-            
-            if current_piece == 0 {
-                return 0; // 空のマスに到達
-            } else if current_piece == self.current_player {
-                return count; // 自分の石に到達
-            } else {
-                count += 1; // 相手の石をカウント
-            }
-            
-            r += dr;
-            c += dc;
+    // これまでの手を AI の最善手と比べて振り返る（対局終了後の振り返り用。履歴を初期配置から再生できない場合はエラー）
+    pub fn review(&self) -> Result<ReviewReport, JsValue> {
+        let mut game = Game::new();
+        for (ply, &(row, col, _)) in self.history.iter().enumerate() {
+            game.play(row, col)
+                .map_err(|e| JsValue::from_str(&format!("{} 手目の {} を再生できません: {}", ply + 1, game::square_name(row, col), e)))?;
         }
-
-        0 // 境界に到達
+        Ok(game.review().into())
     }
 
     // AIの手を実行
@@ -551,6 +846,7 @@ impl OthelloGame {
             let col = ai_move[1] as usize;
             self.make_move(row, col);
             self.current_player = if self.current_player == 1 { 2 } else { 1 };
+            self.refresh_heatmap();
             self.draw_board()?;
             Ok(true)
         } else {
@@ -558,6 +854,17 @@ impl OthelloGame {
         }
     }
 
+    // 手番のプレイヤーに有効な手がない場合に相手に手番を渡す。パスした場合は true を返す
+    pub fn pass(&mut self) -> Result<bool, JsValue> {
+        if self.get_valid_moves_count() > 0 || self.is_game_over() {
+            return Ok(false);
+        }
+        self.current_player = if self.current_player == 1 { 2 } else { 1 };
+        self.refresh_heatmap();
+        self.draw_board()?;
+        Ok(true)
+    }
+
     // ゲームが終了しているかチェック
     pub fn is_game_over(&self) -> bool {
         // 盤面が満杯かチェック
@@ -616,7 +923,7 @@ impl OthelloGame {
         let mut c = col as i32 + dc;
         let mut found_opponent = false;
 
-        while r >= 0 && r < 8 && c >= 0 && c < 8 {
+        while (0..8).contains(&r) && (0..8).contains(&c) {
             let current_piece = self.board[r as usize][c as usize];
             
             if current_piece == 0 {
@@ -633,185 +940,4 @@ impl OthelloGame {
 
         false
     }
-
-    // ボード状態用の有効な手を取得
-    fn get_valid_moves_for_board(&self, board: &BoardState, player: i8) -> Vec<(usize, usize)> {
-        let mut moves = Vec::new();
-        for row in 0..8 {
-            for col in 0..8 {
-                if self.is_valid_move_for_board(board, row, col, player) {
-                    moves.push((row, col));
-                }
-            }
-        }
-        moves
-    }
-
-    // ボード状態での有効な手チェック
-    fn is_valid_move_for_board(&self, board: &BoardState, row: usize, col: usize, player: i8) -> bool {
-        if board.board[row][col] != 0 {
-            return false;
-        }
-
-        let directions = [
-            (-1, -1), (-1, 0), (-1, 1),
-            (0, -1),           (0, 1),
-            (1, -1),  (1, 0),  (1, 1),
-        ];
-
-        for (dr, dc) in directions.iter() {
-            if board.can_flip_in_direction_board(row, col, *dr, *dc, player) {
-                return true;
-            }
-        }
-
-        false
-    }
-
-    // 終端状態かどうかをチェック
-    fn is_terminal_state(&self, board: &BoardState) -> bool {
-        // 盤面が満杯または両プレイヤーに有効な手がない
-        let mut empty_count = 0;
-        for row in 0..8 {
-            for col in 0..8 {
-                if board.board[row][col] == 0 {
-                    empty_count += 1;
-                }
-            }
-        }
-        
-        if empty_count == 0 {
-            return true;
-        }
-
-        // 両プレイヤーに有効な手があるかチェック
-        let has_moves_1 = !self.get_valid_moves_for_board(board, 1).is_empty();
-        let has_moves_2 = !self.get_valid_moves_for_board(board, 2).is_empty();
-        
-        !has_moves_1 && !has_moves_2
-    }
-
-    // ボード状態を評価（改良版評価関数）
-    fn evaluate_board(&self, board: &BoardState) -> i32 {
-        let mut score = 0;
-
-        // 石の数による評価
-        let mut my_count = 0;
-        let mut opponent_count = 0;
-        
-        // 位置による重み付け（戦略的）
-        let position_weights = [
-            [120, -20,  20,   5,   5,  20, -20, 120],
-            [-20, -40,  -5,  -5,  -5,  -5, -40, -20],
-            [ 20,  -5,  15,   3,   3,  15,  -5,  20],
-            [  5,  -5,   3,   3,   3,   3,  -5,   5],
-            [  5,  -5,   3,   3,   3,   3,  -5,   5],
-            [ 20,  -5,  15,   3,   3,  15,  -5,  20],
-            [-20, -40,  -5,  -5,  -5,  -5, -40, -20],
-            [120, -20,  20,   5,   5,  20, -20, 120],
-        ];
-
-        for row in 0..8 {
-            for col in 0..8 {
-                match board.board[row][col] {
-                    piece if piece == self.current_player => {
-                        my_count += 1;
-                        score += position_weights[row][col];
-                    },
-                    piece if piece != 0 => {
-                        opponent_count += 1;
-                        score -= position_weights[row][col];
-                    },
-                    _ => {}
-                }
-            }
-        }
-
-        // 序盤・中盤・終盤で評価を調整
-        let total_pieces = my_count + opponent_count;
-        
-        if total_pieces < 20 {
-            // 序盤: 位置を重視
-            score *= 2;
-        } else if total_pieces < 50 {
-            // 中盤: モビリティ（機動性）を追加
-            let my_mobility = self.get_valid_moves_for_board(board, self.current_player).len() as i32;
-            let opponent_mobility = self.get_valid_moves_for_board(board, 3 - self.current_player).len() as i32;
-            score += (my_mobility - opponent_mobility) * 10;
-        } else {
-            // 終盤: 石の数を重視
-            score += (my_count - opponent_count) * 10;
-        }
-
-        // 安定性の評価（角と辺）
-        score += self.evaluate_stability(board);
-
-        score
-    }
-
-    // 安定性を評価（角と辺の制御）
-    fn evaluate_stability(&self, board: &BoardState) -> i32 {
-        let mut stability_score = 0;
-        
-        // 角の評価
-        let corners = [(0, 0), (0, 7), (7, 0), (7, 7)];
-        for &(row, col) in &corners {
-            match board.board[row][col] {
-                piece if piece == self.current_player => stability_score += 25,
-                piece if piece != 0 => stability_score -= 25,
-                _ => {}
-            }
-        }
-
-        // 辺の評価
-        for i in 0..8 {
-            // 上辺と下辺
-            if board.board[0][i] == self.current_player { stability_score += 5; }
-            else if board.board[0][i] != 0 { stability_score -= 5; }
-            
-            if board.board[7][i] == self.current_player { stability_score += 5; }
-            else if board.board[7][i] != 0 { stability_score -= 5; }
-            
-            // 左辺と右辺
-            if board.board[i][0] == self.current_player { stability_score += 5; }
-            else if board.board[i][0] != 0 { stability_score -= 5; }
-            
-            if board.board[i][7] == self.current_player { stability_score += 5; }
-            else if board.board[i][7] != 0 { stability_score -= 5; }
-        }
-
-        stability_score
-    }
-
-    // 基本的な手の評価スコア（貪欲法用）
-    fn calculate_basic_move_score(&self, row: usize, col: usize) -> i32 {
-        let mut score = 0;
-
-        // 位置による重み付け（戦略的）
-        let position_weights = [
-            [100, -20,  10,   5,   5,  10, -20, 100],
-            [-20, -50,  -2,  -2,  -2,  -2, -50, -20],
-            [ 10,  -2,  -1,  -1,  -1,  -1,  -2,  10],
-            [  5,  -2,  -1,  -1,  -1,  -1,  -2,   5],
-            [  5,  -2,  -1,  -1,  -1,  -1,  -2,   5],
-            [ 10,  -2,  -1,  -1,  -1,  -1,  -2,  10],
-            [-20, -50,  -2,  -2,  -2,  -2, -50, -20],
-            [100, -20,  10,   5,   5,  10, -20, 100],
-        ];
-
-        score += position_weights[row][col];
-
-        // ひっくり返せる石の数を計算（少し重み付け）
-        let directions = [
-            (-1, -1), (-1, 0), (-1, 1),
-            (0, -1),           (0, 1),
-            (1, -1),  (1, 0),  (1, 1),
-        ];
-
-        for (dr, dc) in directions.iter() {
-            score += self.count_flips_in_direction(row, col, *dr, *dc) * 2;
-        }
-
-        score
-    }
 }
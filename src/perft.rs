@@ -0,0 +1,88 @@
+// perft（指し手生成の検証）
+//
+// 指定した深さまで全ての手を展開し、末端の局面の数を数える。公開されている値と比べることで、
+// ビットボードの着手可能な手の生成と石を返す処理が正しいことを確かめる。
+// パスも1手と数え、両者とも打てなくなった局面はその深さで末端として数える（一般的なオセロの perft と同じ）。
+
+use crate::bitboard::{squares, Bitboard};
+use crate::game::Game;
+
+// 初期局面からの perft の値（深さ 0 から）
+pub const START_PERFT: [u64; 12] = [1, 4, 12, 56, 244, 1396, 8200, 55092, 390216, 3005288, 24571284, 212258800];
+
+// game の局面から depth 手先までの末端の局面の数
+pub fn perft(game: &Game, depth: u32) -> u64 {
+    count(Bitboard::from_board(game.board_state(), game.current_player()), depth)
+}
+
+// 手ごとの perft（深さ depth の perft を最初の手で分けたもの。値が合わないときに原因の手を絞り込む）
+pub fn divide(game: &Game, depth: u32) -> Vec<((usize, usize), u64)> {
+    let board = Bitboard::from_board(game.board_state(), game.current_player());
+    squares(board.moves())
+        .map(|square| ((square as usize / 8, square as usize % 8), count(board.play(square), depth.saturating_sub(1))))
+        .collect()
+}
+
+fn count(board: Bitboard, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let moves = board.moves();
+    if moves == 0 {
+        let passed = board.pass();
+        if passed.moves() == 0 {
+            return 1; // 終局
+        }
+        return count(passed, depth - 1);
+    }
+    if depth == 1 {
+        return moves.count_ones() as u64;
+    }
+    squares(moves).map(|square| count(board.play(square), depth - 1)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::testing::random_positions;
+    use crate::board::BoardState;
+
+    // 配列の盤面の指し手生成による perft
+    fn count_array(board: &BoardState, player: i8, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let moves = board.get_valid_moves_for_board(player);
+        if moves.is_empty() {
+            if board.get_valid_moves_for_board(3 - player).is_empty() {
+                return 1;
+            }
+            return count_array(board, 3 - player, depth - 1);
+        }
+        moves
+            .iter()
+            .map(|&(row, col)| {
+                let mut child = board.clone();
+                child.make_move_on_board(row, col, player);
+                count_array(&child, 3 - player, depth - 1)
+            })
+            .sum()
+    }
+
+    #[test]
+    fn array_generator_matches_start_perft() {
+        let start = crate::board::testing::start();
+        for (depth, &expected) in START_PERFT.iter().enumerate().take(7) {
+            assert_eq!(count_array(&start, 1, depth as u32), expected, "深さ {}", depth);
+        }
+    }
+
+    #[test]
+    fn bitboard_and_array_perft_agree() {
+        for (board, player) in random_positions(60, 47) {
+            for depth in 1..=3 {
+                assert_eq!(count(Bitboard::from_board(&board, player), depth), count_array(&board, player, depth), "{}", board.to_position_string(player));
+            }
+        }
+    }
+}
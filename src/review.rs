@@ -0,0 +1,500 @@
+// 対局の振り返り
+//
+// 棋譜を並べ直して各手を AI の最善手と比べ、最善手との差（石数）で
+// 最善・好手・緩手・悪手・大悪手に分類し、プレイヤーごとの集計を作る。
+// 残りのマスが少ない局面は完全読みで正確な石数を求め、それより前の局面は
+// 評価関数の探索結果を石数に換算した推定値を使う。
+
+use std::fmt::{self, Write};
+
+use wasm_bindgen::prelude::*;
+
+use crate::ai::{Strategy, WIN_SCORE};
+use crate::bitboard::Bitboard;
+use crate::endgame::Solver;
+use crate::game::{square_name, Game};
+
+// 評価値から石数への換算（空きマス 14〜15 の局面で、深さ 5 の最善手の評価値を完全読みの石差に
+// 最小二乗法で当てはめた比。中級同士の 200 局から 29.1。examples/review_scales.rs で当てはめ直せる）
+const EVAL_PER_DISC: f64 = 29.0;
+
+// 予想石差から勝率への換算の尺度（勝率 = 1 / (1 + exp(-石差 / WIN_SCALE))。推定値にだけ使い、完全読みでは勝敗が確定する。
+// 上と同じ 200 局面の予想石差と完全読みの勝敗から最尤推定した値）
+const WIN_SCALE: f64 = 12.5;
+
+// 評価値を予想される最終石差に換算（手番側から見た値）。終局まで読み切った値は WIN_SCORE を除いた石差そのもので、
+// 評価関数の値は EVAL_PER_DISC で割って、最終石差として取りうる ±64 の範囲に収める
+pub fn eval_to_discs(value: i32) -> i32 {
+    if value.abs() >= WIN_SCORE {
+        value - value.signum() * WIN_SCORE
+    } else {
+        (value as f64 / EVAL_PER_DISC).round().clamp(-64.0, 64.0) as i32
+    }
+}
+
+// 手の分類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveClass {
+    Best,       // 最善手
+    Good,       // 最善手との差が 2 石以内
+    Inaccuracy, // 5 石以内
+    Mistake,    // 10 石以内
+    Blunder,    // 10 石を超える
+}
+
+impl MoveClass {
+    pub const ALL: [MoveClass; 5] = [MoveClass::Best, MoveClass::Good, MoveClass::Inaccuracy, MoveClass::Mistake, MoveClass::Blunder];
+
+    // 最善手との差（石数）から分類する
+    pub fn from_disc_loss(disc_loss: i32) -> Self {
+        match disc_loss {
+            i32::MIN..=0 => MoveClass::Best,
+            1..=2 => MoveClass::Good,
+            3..=5 => MoveClass::Inaccuracy,
+            6..=10 => MoveClass::Mistake,
+            _ => MoveClass::Blunder,
+        }
+    }
+
+    // 表示用の名前
+    pub fn label(self) -> &'static str {
+        match self {
+            MoveClass::Best => "最善",
+            MoveClass::Good => "好手",
+            MoveClass::Inaccuracy => "緩手",
+            MoveClass::Mistake => "悪手",
+            MoveClass::Blunder => "大悪手",
+        }
+    }
+}
+
+// 振り返りの設定
+#[derive(Debug, Clone, Copy)]
+pub struct ReviewOptions {
+    pub depth: u8,          // 完全読みしない局面の探索の深さ
+    pub exact_empties: u32, // 空きマスがこの数以下の局面は完全読みする
+}
+
+impl Default for ReviewOptions {
+    fn default() -> Self {
+        ReviewOptions { depth: 5, exact_empties: 14 }
+    }
+}
+
+// 1手の振り返り
+#[derive(Debug, Clone)]
+pub struct MoveReview {
+    pub ply: usize, // 何手目か（0 から）
+    pub row: usize,
+    pub col: usize,
+    pub player: i8,
+    pub best: (usize, usize), // 最善手
+    pub score: i32,      // 打った手の後の予想される最終石差（打った側から見た値）
+    pub best_score: i32, // 最善手を打った場合の予想される最終石差
+    pub disc_loss: i32,  // 最善手との差（石数）
+    pub exact: bool,     // 完全読みによる正確な値か
+    pub forced: bool,    // 有効な手が1つしかなかったか
+    pub class: MoveClass,
+}
+
+// プレイヤーごとの集計
+#[derive(Debug, Clone, Default)]
+pub struct PlayerSummary {
+    pub moves: usize,
+    pub counts: [usize; 5], // MoveClass::ALL の順の手の数
+    pub total_loss: i32,    // 最善手との差の合計（石数）
+}
+
+impl PlayerSummary {
+    pub fn count(&self, class: MoveClass) -> usize {
+        self.counts[class as usize]
+    }
+
+    // 1手あたりの平均の損失（石数）
+    pub fn average_loss(&self) -> f64 {
+        if self.moves == 0 {
+            0.0
+        } else {
+            self.total_loss as f64 / self.moves as f64
+        }
+    }
+
+    // 最善手または好手だった割合（0〜1）
+    pub fn accuracy(&self) -> f64 {
+        if self.moves == 0 {
+            1.0
+        } else {
+            (self.count(MoveClass::Best) + self.count(MoveClass::Good)) as f64 / self.moves as f64
+        }
+    }
+}
+
+// 評価値の推移の1点（黒から見た値）
+#[derive(Debug, Clone, Copy)]
+pub struct EvaluationPoint {
+    pub ply: usize,           // この点までに打たれた手の数
+    pub advantage: i32,       // 予想される最終石差（黒 - 白）
+    pub win_probability: f64, // 黒の勝率（0〜1、引き分けは 0.5）
+    pub exact: bool,          // 完全読みによる値か
+}
+
+impl EvaluationPoint {
+    fn new(ply: usize, advantage: i32, exact: bool) -> Self {
+        let win_probability = if exact {
+            0.5 + 0.5 * advantage.signum() as f64
+        } else {
+            1.0 / (1.0 + (-advantage as f64 / WIN_SCALE).exp())
+        };
+        EvaluationPoint { ply, advantage, win_probability, exact }
+    }
+}
+
+// 対局全体の振り返り
+#[derive(Debug, Clone)]
+pub struct GameReview {
+    pub moves: Vec<MoveReview>,
+    pub black: PlayerSummary,
+    pub white: PlayerSummary,
+}
+
+impl GameReview {
+    pub fn summary(&self, player: i8) -> &PlayerSummary {
+        if player == 1 { &self.black } else { &self.white }
+    }
+
+    // 各手を打つ前の局面と最後の手の後の局面の評価（最初の点は最初の手の前の局面の最善の評価、
+    // 以降は打った手の評価）
+    pub fn evaluation_series(&self) -> Vec<EvaluationPoint> {
+        let black_view = |review: &MoveReview, score: i32| if review.player == 1 { score } else { -score };
+        let mut series = Vec::with_capacity(self.moves.len() + 1);
+        if let Some(first) = self.moves.first() {
+            series.push(EvaluationPoint::new(0, black_view(first, first.best_score), first.exact));
+        }
+        for review in &self.moves {
+            series.push(EvaluationPoint::new(review.ply + 1, black_view(review, review.score), review.exact));
+        }
+        series
+    }
+
+    // 評価値の推移のテキストのグラフ（上が黒優勢、下が白優勢。height は片側の行数）
+    pub fn ascii_chart(&self, height: usize) -> String {
+        let series = self.evaluation_series();
+        let height = height.max(1);
+        // 縦軸の最大値は 8 石単位で切り上げる
+        let peak = series.iter().map(|point| point.advantage.abs()).max().unwrap_or(0);
+        let scale = ((peak + 7) / 8).max(1) * 8;
+        let level = |advantage: i32| (advantage as f64 * height as f64 / scale as f64).round() as i32;
+
+        let mut text = String::new();
+        for row in (1..=height as i32).rev().chain(std::iter::once(0)).chain((1..=height as i32).map(|row| -row)) {
+            let label = match row {
+                0 => "   0".to_string(),
+                _ if row == height as i32 => format!("{:+4}", scale),
+                _ if row == -(height as i32) => format!("{:+4}", -scale),
+                _ => "    ".to_string(),
+            };
+            let line: String = series
+                .iter()
+                .map(|point| {
+                    let value = level(point.advantage);
+                    match row {
+                        0 => '-',
+                        _ if row > 0 && value >= row => 'X',
+                        _ if row < 0 && value <= row => 'O',
+                        _ => ' ',
+                    }
+                })
+                .collect();
+            let _ = writeln!(text, "{} |{}", label, line.trim_end());
+        }
+
+        // 横軸（10 手ごとに目盛り）
+        let axis: String = (0..series.len()).map(|ply| if ply.is_multiple_of(10) { '+' } else { '-' }).collect();
+        let labels: String = (0..series.len())
+            .step_by(10)
+            .map(|ply| format!("{:<10}", ply))
+            .collect();
+        let _ = writeln!(text, "     -{}", axis);
+        let _ = writeln!(text, "      {}", labels.trim_end());
+        text
+    }
+
+    // 損失の大きい順に並べた悪手・大悪手
+    pub fn worst_moves(&self) -> Vec<&MoveReview> {
+        let mut moves: Vec<&MoveReview> = self.moves.iter().filter(|review| review.class as usize >= MoveClass::Mistake as usize).collect();
+        moves.sort_by_key(|review| (std::cmp::Reverse(review.disc_loss), review.ply));
+        moves
+    }
+}
+
+impl fmt::Display for GameReview {
+    // 集計と悪手の一覧（UI やコマンドラインでそのまま表示できる形式）
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, summary) in [("黒", &self.black), ("白", &self.white)] {
+            let counts: Vec<String> = MoveClass::ALL.iter().map(|&class| format!("{} {}", class.label(), summary.count(class))).collect();
+            writeln!(
+                f,
+                "{}: {} 手  {}  平均損失 {:.1} 石  正確さ {:.0}%",
+                name,
+                summary.moves,
+                counts.join(" / "),
+                summary.average_loss(),
+                summary.accuracy() * 100.0
+            )?;
+        }
+        for review in self.worst_moves() {
+            writeln!(
+                f,
+                "{}手目 {} {}: {} ({} 石損、最善は {}){}",
+                review.ply + 1,
+                if review.player == 1 { "黒" } else { "白" },
+                square_name(review.row, review.col),
+                review.class.label(),
+                review.disc_loss,
+                square_name(review.best.0, review.best.1),
+                if review.exact { "" } else { " ※推定" }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// 対局を振り返る（対局の途中でも、それまでの手を振り返る）
+pub fn review_game(game: &Game, options: &ReviewOptions) -> GameReview {
+    let mut review = GameReview {
+        moves: Vec::new(),
+        black: PlayerSummary::default(),
+        white: PlayerSummary::default(),
+    };
+
+    for (ply, (position, (row, col, player))) in game.replay().into_iter().enumerate() {
+        let (evaluations, exact) = evaluate_position(&position, options);
+        let Some(&(_, played)) = evaluations.iter().find(|&&(square, _)| square == (row, col)) else {
+            continue;
+        };
+        let Some(&(best, best_value)) = evaluations.iter().reduce(|best, evaluation| if evaluation.1 > best.1 { evaluation } else { best }) else {
+            continue;
+        };
+
+        let to_discs = |value: i32| if exact { value } else { eval_to_discs(value) };
+        let (score, best_score) = (to_discs(played), to_discs(best_value));
+        let disc_loss = (best_score - score).max(0);
+        let class = MoveClass::from_disc_loss(disc_loss);
+        let summary = if player == 1 { &mut review.black } else { &mut review.white };
+        summary.moves += 1;
+        summary.counts[class as usize] += 1;
+        summary.total_loss += disc_loss;
+
+        review.moves.push(MoveReview {
+            ply,
+            row,
+            col,
+            player,
+            best: if disc_loss > 0 { best } else { (row, col) },
+            score,
+            best_score,
+            disc_loss,
+            exact,
+            forced: evaluations.len() == 1,
+            class,
+        });
+    }
+    review
+}
+
+// 各有効手の評価（完全読みなら石差、そうでなければ評価値）
+type Evaluations = Vec<((usize, usize), i32)>;
+
+// 局面の各有効手の評価と、完全読みかどうか
+fn evaluate_position(game: &Game, options: &ReviewOptions) -> (Evaluations, bool) {
+    let bitboard = Bitboard::from_board(game.board_state(), game.current_player());
+    if bitboard.empties() <= options.exact_empties {
+        let evaluations = Solver::new()
+            .solve_moves(bitboard)
+            .into_iter()
+            .map(|(square, score)| ((square as usize / 8, square as usize % 8), score))
+            .collect();
+        return (evaluations, true);
+    }
+
+    (game.evaluate_moves_with(Strategy::AlphaBeta(options.depth)), false)
+}
+
+// 初期配置からの棋譜（"f5d6c3..." の形式）を振り返る（探索に時間がかかるため、ブラウザでは Web Worker から呼ぶ）
+#[wasm_bindgen]
+pub fn review_transcript(transcript: &str) -> Result<ReviewReport, JsValue> {
+    let mut game = Game::new();
+    game.play_transcript(transcript).map_err(|e| JsValue::from_str(&e))?;
+    Ok(game.review().into())
+}
+
+// JavaScript から使う振り返りの結果
+#[wasm_bindgen]
+pub struct ReviewReport {
+    inner: GameReview,
+    series: Vec<EvaluationPoint>, // 評価値の推移（advantages と win_probabilities で共有する）
+}
+
+impl From<GameReview> for ReviewReport {
+    fn from(inner: GameReview) -> Self {
+        let series = inner.evaluation_series();
+        ReviewReport { inner, series }
+    }
+}
+
+#[wasm_bindgen]
+impl ReviewReport {
+    // 振り返った手の数
+    #[wasm_bindgen(getter)]
+    pub fn move_count(&self) -> usize {
+        self.inner.moves.len()
+    }
+
+    // index 番目の手（[行, 列, 手番, 最善手の行, 最善手の列, 予想石差, 最善手の予想石差, 損失, 分類, 完全読みなら 1] の形式。
+    // 分類は 0: 最善, 1: 好手, 2: 緩手, 3: 悪手, 4: 大悪手）
+    pub fn move_at(&self, index: usize) -> Vec<i32> {
+        let Some(review) = self.inner.moves.get(index) else {
+            return Vec::new();
+        };
+        vec![
+            review.row as i32,
+            review.col as i32,
+            review.player as i32,
+            review.best.0 as i32,
+            review.best.1 as i32,
+            review.score,
+            review.best_score,
+            review.disc_loss,
+            review.class as i32,
+            review.exact as i32,
+        ]
+    }
+
+    // index 番目の手の分類の名前
+    pub fn class_label(&self, index: usize) -> String {
+        self.inner.moves.get(index).map_or("", |review| review.class.label()).to_string()
+    }
+
+    // プレイヤー（1: 黒, 2: 白）の分類ごとの手の数（最善, 好手, 緩手, 悪手, 大悪手の順）
+    pub fn counts(&self, player: i8) -> Vec<u32> {
+        self.inner.summary(player).counts.iter().map(|&count| count as u32).collect()
+    }
+
+    // プレイヤーの1手あたりの平均損失（石数）
+    pub fn average_loss(&self, player: i8) -> f64 {
+        self.inner.summary(player).average_loss()
+    }
+
+    // プレイヤーの最善手または好手の割合（0〜1）
+    pub fn accuracy(&self, player: i8) -> f64 {
+        self.inner.summary(player).accuracy()
+    }
+
+    // 集計と悪手の一覧のテキスト
+    pub fn summary(&self) -> String {
+        self.inner.to_string()
+    }
+
+    // 評価値の推移（各局面の予想される最終石差。黒から見た値で、最初の手の前の局面から順に手の数 + 1 個）
+    pub fn advantages(&self) -> Vec<i32> {
+        self.series.iter().map(|point| point.advantage).collect()
+    }
+
+    // 黒の勝率の推移（0〜1。advantages と同じ局面）
+    pub fn win_probabilities(&self) -> Vec<f64> {
+        self.series.iter().map(|point| point.win_probability).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proven_results_decode_to_exact_discs() {
+        assert_eq!(eval_to_discs(WIN_SCORE + 12), 12);
+        assert_eq!(eval_to_discs(-WIN_SCORE - 4), -4);
+        assert_eq!(eval_to_discs((EVAL_PER_DISC * 3.0) as i32), 3);
+        assert_eq!(eval_to_discs(-(EVAL_PER_DISC * 100.0) as i32), -64);
+    }
+
+    #[test]
+    fn wipeout_is_reviewed_with_the_final_disc_count() {
+        // 9手で白の石がなくなる終局（最後の手は読み切れるので推定値でも 13 石勝ち）
+        let mut game = Game::new();
+        game.play_transcript("d3c3b3d2e1d6d7e3f4").unwrap();
+        let review = game.review();
+        assert_eq!(review.moves.len(), 9);
+
+        let last = review.moves.last().unwrap();
+        assert!(!last.exact);
+        assert_eq!((last.score, last.best_score, last.disc_loss), (13, 13, 0));
+        assert_eq!(last.class, MoveClass::Best);
+        for review in &review.moves {
+            assert!(review.score.abs() <= 64 && review.best_score.abs() <= 64);
+            assert!((0..=128).contains(&review.disc_loss));
+        }
+    }
+
+    #[test]
+    fn x_square_move_is_a_mistake() {
+        // 11 手目に黒が角の空いている X マス (b7) に打つ（最善の c7・e7・d8 より 6 石損）
+        let mut game = Game::new();
+        game.play_transcript("f5d6c5f4e3c6d3f6e6d7b7").unwrap();
+        let review = game.review();
+        let last = review.moves.last().unwrap();
+        assert_eq!((last.row, last.col, last.player), (6, 1, 1));
+        assert!(!last.exact);
+        assert_eq!(last.disc_loss, 6);
+        assert_eq!(last.class, MoveClass::Mistake);
+        assert_ne!(last.best, (6, 1));
+        assert_eq!(review.black.counts[MoveClass::Mistake as usize], 1);
+    }
+
+    fn move_review(ply: usize, player: i8, score: i32, best_score: i32, exact: bool) -> MoveReview {
+        let disc_loss = (best_score - score).max(0);
+        MoveReview {
+            ply,
+            row: 0,
+            col: 0,
+            player,
+            best: (0, 0),
+            score,
+            best_score,
+            disc_loss,
+            exact,
+            forced: false,
+            class: MoveClass::from_disc_loss(disc_loss),
+        }
+    }
+
+    #[test]
+    fn evaluation_series_and_chart() {
+        let review = GameReview {
+            moves: vec![move_review(0, 1, 2, 4, false), move_review(1, 2, 1, 1, false), move_review(2, 1, 12, 12, true)],
+            black: PlayerSummary::default(),
+            white: PlayerSummary::default(),
+        };
+        let report = ReviewReport::from(review.clone());
+        // 最初の点は最初の手の前の最善の評価、白の手の値は黒から見た値に直す
+        assert_eq!(report.advantages(), vec![4, 2, -1, 12]);
+        let probabilities = report.win_probabilities();
+        assert!((probabilities[0] - 1.0 / (1.0 + (-4.0 / WIN_SCALE).exp())).abs() < 1e-12);
+        assert!(probabilities[1] > 0.5 && probabilities[2] < 0.5);
+        assert_eq!(probabilities[3], 1.0);
+
+        assert_eq!(
+            review.ascii_chart(2),
+            " +16 |   X\n     |X  X\n   0 |----\n     |\n -16 |\n     -+---\n      0\n"
+        );
+    }
+
+    #[test]
+    fn empty_game_has_no_series() {
+        let review = Game::new().review();
+        assert!(review.moves.is_empty());
+        let report = ReviewReport::from(review.clone());
+        assert!(report.advantages().is_empty() && report.win_probabilities().is_empty());
+        assert_eq!(review.ascii_chart(1), "  +8 |\n   0 |\n  -8 |\n     -\n      \n");
+    }
+}
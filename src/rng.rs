@@ -0,0 +1,40 @@
+// 再現可能な疑似乱数（splitmix64）
+//
+// 同じシードからは常に同じ乱数列が得られるため、AI の手のばらつきを
+// テストや対局の再現でそのまま再現できる。
+
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // [0, 1) の一様乱数
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // [0, n) の整数（n は 1 以上）
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    // [-amplitude, amplitude] の整数
+    pub(crate) fn spread(&mut self, amplitude: i32) -> i32 {
+        if amplitude <= 0 {
+            return 0;
+        }
+        self.below(amplitude as usize * 2 + 1) as i32 - amplitude
+    }
+}
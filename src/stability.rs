@@ -0,0 +1,202 @@
+// 確定石（この先どう打たれても二度と返されない石）の判定
+//
+// 縦・横・2つの斜めの4本の線それぞれについて、線が端まで埋まっているか、
+// 線上の隣の片側が同じ色の確定石（または盤外）であれば、その線の向きには返されない。
+// 4本すべてで返されない石を確定石とし、見つかった確定石を足場にして増えなくなるまで繰り返す。
+// 角から辺に沿って続く石だけでなく、埋まった列に挟まれた内側の石も確定石として数えられる。
+
+use crate::bitboard::{shift, Bitboard};
+use crate::board::BoardState;
+
+const ALL: u64 = !0;
+
+// 4本の線の向き（シフト量と、正・負の向きにシフトするときのマスク）
+const AXES: [(i32, u64, u64); 4] = [
+    (1, 0xfefe_fefe_fefe_fefe, 0x7f7f_7f7f_7f7f_7f7f), // 横
+    (8, ALL, ALL),                                     // 縦
+    (9, 0xfefe_fefe_fefe_fefe, 0x7f7f_7f7f_7f7f_7f7f), // 斜め（左上〜右下）
+    (7, 0x7f7f_7f7f_7f7f_7f7f, 0xfefe_fefe_fefe_fefe), // 斜め（右上〜左下）
+];
+
+// 向きごとの盤上の全ての線（横 8 本・縦 8 本・斜め 15 本ずつ）
+const LINES: [[u64; 15]; 4] = {
+    let mut lines = [[0; 15]; 4];
+    let mut square = 0;
+    while square < 64 {
+        let (row, col) = (square / 8, square % 8);
+        let bit = 1u64 << square;
+        lines[0][row] |= bit;
+        lines[1][col] |= bit;
+        lines[2][row + 7 - col] |= bit;
+        lines[3][row + col] |= bit;
+        square += 1;
+    }
+    lines
+};
+
+// 向きごとに、端まで埋まっている線に含まれるマスの集合
+fn full_lines(occupied: u64) -> [u64; 4] {
+    let mut full = [0; 4];
+    for (axis, lines) in LINES.iter().enumerate() {
+        for &line in lines {
+            if line != 0 && occupied & line == line {
+                full[axis] |= line;
+            }
+        }
+    }
+    full
+}
+
+// own の石のうち確定石の集合（other は相手の石）
+pub(crate) fn stable_discs(own: u64, other: u64) -> u64 {
+    let full = full_lines(own | other);
+    let mut stable = 0;
+    loop {
+        let mut next = own;
+        for (axis, &(amount, forward, backward)) in AXES.iter().enumerate() {
+            // 線上の隣（正の向き・負の向き）が確定石か盤外のマス
+            let before = shift(stable, amount, forward) | !shift(ALL, amount, forward);
+            let after = shift(stable, -amount, backward) | !shift(ALL, -amount, backward);
+            next &= full[axis] | before | after;
+        }
+        if next == stable {
+            return stable;
+        }
+        stable = next;
+    }
+}
+
+impl Bitboard {
+    // 手番側の確定石
+    pub(crate) fn stable(self) -> u64 {
+        stable_discs(self.player, self.opponent)
+    }
+
+    // 相手側の確定石
+    pub(crate) fn opponent_stable(self) -> u64 {
+        stable_discs(self.opponent, self.player)
+    }
+}
+
+// 盤面で player の確定石の数
+pub(crate) fn count_stable(board: &BoardState, player: i8) -> usize {
+    Bitboard::from_board(board, player).stable().count_ones() as usize
+}
+
+// 盤面で player の確定石のマス（行, 列）の一覧（左上から順）
+pub(crate) fn stable_squares(board: &BoardState, player: i8) -> Vec<(usize, usize)> {
+    let stable = Bitboard::from_board(board, player).stable();
+    (0..64).filter(|&square| stable & (1u64 << square) != 0).map(|square| (square / 8, square % 8)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::testing::{random_positions, start};
+    use crate::rng::Rng;
+
+    // 局面文字列（8行ずつ区切って書いたもの）から盤面を作る
+    fn board(rows: &[&str; 8]) -> BoardState {
+        BoardState::from_position_string(&format!("{} X", rows.concat())).unwrap().0
+    }
+
+    #[test]
+    fn start_position_has_no_stable_discs() {
+        assert!(stable_squares(&start(), 1).is_empty());
+        assert!(stable_squares(&start(), 2).is_empty());
+    }
+
+    #[test]
+    fn interior_disc_on_full_lines() {
+        // d4 を通る横・縦・2本の斜めがすべて埋まっている（それ以外は空き）
+        let full = board(&[
+            "X--O--X-",
+            "-X-X-O--",
+            "--OXO---",
+            "XOOXOXXO",
+            "--XOX---",
+            "-O-X-X--",
+            "O--O--O-",
+            "---X---X",
+        ]);
+        assert!(stable_squares(&full, 1).contains(&(3, 3)));
+
+        // 斜めの1マス（g7）が空くと、d4 はその向きに返される可能性が残る
+        let open = board(&[
+            "X--O--X-",
+            "-X-X-O--",
+            "--OXO---",
+            "XOOXOXXO",
+            "--XOX---",
+            "-O-X-X--",
+            "O--O----",
+            "---X---X",
+        ]);
+        assert!(!stable_squares(&open, 1).contains(&(3, 3)));
+    }
+
+    #[test]
+    fn edge_chain_anchored_at_a_corner() {
+        let board = board(&[
+            "XXXXO-X-",
+            "--------",
+            "--------",
+            "---OX---",
+            "---XO---",
+            "--------",
+            "--------",
+            "--------",
+        ]);
+        // a1 から続く黒は返されない。g1 の黒は角につながっておらず、e1 の白は f1 に黒が置けば返される
+        assert_eq!(stable_squares(&board, 1), vec![(0, 0), (0, 1), (0, 2), (0, 3)]);
+        assert!(stable_squares(&board, 2).is_empty());
+    }
+
+    #[test]
+    fn flippable_disc_is_not_stable() {
+        // b1 は a1 が空いているため、a1 に白が置けば返される
+        let board = board(&[
+            "-XO-----",
+            "--------",
+            "--------",
+            "---OX---",
+            "---XO---",
+            "--------",
+            "--------",
+            "--------",
+        ]);
+        assert!(stable_squares(&board, 1).is_empty());
+        assert!(stable_squares(&board, 2).is_empty());
+    }
+
+    #[test]
+    fn stable_discs_are_never_flipped() {
+        let mut rng = Rng::new(44);
+        for (start, first) in random_positions(300, 7) {
+            let stable = [stable_squares(&start, 1), stable_squares(&start, 2)];
+            // 同じ局面から何通りか無作為に打ち進めて、確定石の色が変わらないことを確かめる
+            for _ in 0..4 {
+                let mut board = start.clone();
+                let mut player = first;
+                for _ in 0..12 {
+                    let moves = board.get_valid_moves_for_board(player);
+                    if moves.is_empty() {
+                        if board.get_valid_moves_for_board(3 - player).is_empty() {
+                            break;
+                        }
+                    } else {
+                        let (row, col) = moves[rng.below(moves.len())];
+                        board.make_move_on_board(row, col, player);
+                    }
+                    player = 3 - player;
+
+                    for (index, squares) in stable.iter().enumerate() {
+                        for &(row, col) in squares {
+                            assert_eq!(board.board[row][col], index as i8 + 1, "{} の確定石が返されました", start.to_position_string(first));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
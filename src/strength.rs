@@ -0,0 +1,217 @@
+// 連続的な強さ（0〜100）とレーティングの対応
+//
+// 強さから探索の深さ・評価値に加える乱数の幅・悪手を打つ確率を決める。
+// 各深さの強さでは乱数を加えず、その間の強さでは深い方の探索に乱数を加えて弱める。
+// 4つの難易度はこの尺度上の点に対応し、その点では難易度と同じ手を選ぶ。
+// 強さとレーティングの対応表は AI 同士の対局（tournament --calibrate）で求めた。
+
+use crate::ai::{AiConfig, AiDifficulty, Strategy};
+
+// 各深さの探索をそのまま使う強さ（深さ 0 は貪欲法。深さ 1 は貪欲法より弱かったため使わない）
+const DEPTH_POINTS: [(f64, u8); 8] = [
+    (25.0, 0),
+    (42.0, 2),
+    (50.0, 3),
+    (62.0, 4),
+    (75.0, 5),
+    (83.0, 6),
+    (90.0, 7),
+    (100.0, 8),
+];
+
+// 深さの間の強さで加える乱数の最大幅（評価値で約 2.8 石。深さ d に加えると深さ d - 1 とほぼ互角になる値）
+const MAX_NOISE: f64 = 80.0;
+
+// 強さ 0 で悪手を打つ確率
+const MAX_BLUNDER: f64 = 0.5;
+
+// 強さとレーティングの対応（強さ 0 を 0 とした相対値）。0〜60 は
+// tournament --calibrate --levels 0,10,20,30,40,50,60 --openings 16（各組 32 局）の最尤推定値。
+// 深さ 6〜8 の対局は時間がかかるため、60 から上は隣の強さとの tournament --calibrate --levels 60,70 のような
+// 直接対決の差を積み上げた（60-70 と 70-80 は 32 局で +132 と +228、80-90 と 90-100 は 16 局で +235 と +128）
+const CALIBRATION: [(f64, f64); 11] = [
+    (0.0, 0.0),
+    (10.0, 124.0),
+    (20.0, 273.0),
+    (30.0, 696.0),
+    (40.0, 840.0),
+    (50.0, 984.0),
+    (60.0, 1275.0),
+    (70.0, 1407.0),
+    (80.0, 1635.0),
+    (90.0, 1870.0),
+    (100.0, 1998.0),
+];
+
+// from_elo は対応表を逆に引くため、強さとレーティングはどちらも狭義単調増加でなければならない
+const _: () = {
+    let mut index = 1;
+    while index < CALIBRATION.len() {
+        assert!(CALIBRATION[index].0 > CALIBRATION[index - 1].0 && CALIBRATION[index].1 > CALIBRATION[index - 1].1);
+        index += 1;
+    }
+};
+
+// 連続的な強さ（0〜100）
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Strength(f64);
+
+impl Strength {
+    pub fn new(value: f64) -> Self {
+        Strength(if value.is_nan() { 0.0 } else { value.clamp(0.0, 100.0) })
+    }
+
+    pub fn value(self) -> f64 {
+        self.0
+    }
+
+    // 強さに対応する AI の設定
+    pub fn config(self) -> AiConfig {
+        let strength = self.0;
+        let (first_point, _) = DEPTH_POINTS[0];
+        if strength <= first_point {
+            // 貪欲法に乱数と悪手を加える
+            let weakness = (first_point - strength) / first_point;
+            return AiConfig {
+                noise: (MAX_NOISE * weakness).round() as i32,
+                blunder: MAX_BLUNDER * weakness,
+                ..AiConfig::from(Strategy::Greedy)
+            };
+        }
+
+        // strength 以上で最初の深さの点を使い、手前の点との距離に応じて乱数を加える
+        let index = DEPTH_POINTS.iter().position(|&(point, _)| strength <= point).unwrap_or(DEPTH_POINTS.len() - 1);
+        let (point, depth) = DEPTH_POINTS[index];
+        let (previous, _) = DEPTH_POINTS[index.saturating_sub(1)];
+        let weakness = if point > previous { ((point - strength) / (point - previous)).clamp(0.0, 1.0) } else { 0.0 };
+        AiConfig {
+            noise: (MAX_NOISE * weakness).round() as i32,
+            ..AiConfig::from(strategy_for_depth(depth))
+        }
+    }
+
+    // 推定レーティング（強さ 0 を 0 とした相対値）
+    pub fn elo(self) -> f64 {
+        interpolate(&CALIBRATION, self.0)
+    }
+
+    // 目標のレーティングに最も近い強さ
+    pub fn from_elo(elo: f64) -> Self {
+        let inverse: Vec<(f64, f64)> = CALIBRATION.iter().map(|&(strength, elo)| (elo, strength)).collect();
+        Strength::new(interpolate(&inverse, elo))
+    }
+}
+
+impl From<AiDifficulty> for Strength {
+    fn from(difficulty: AiDifficulty) -> Self {
+        let depth = match difficulty.strategy() {
+            Strategy::Greedy => 0,
+            Strategy::Minimax(depth) | Strategy::AlphaBeta(depth) => depth,
+        };
+        let point = DEPTH_POINTS.iter().find(|&&(_, d)| d == depth).map_or(0.0, |&(point, _)| point);
+        Strength::new(point)
+    }
+}
+
+// 深さに対応する探索方法（深さ 0 は貪欲法）。
+// アルファベータ法はルートの各手を全幅の窓で探索するため、同じ深さのミニマックス法と同じ評価値になる
+fn strategy_for_depth(depth: u8) -> Strategy {
+    if depth == 0 {
+        Strategy::Greedy
+    } else {
+        Strategy::AlphaBeta(depth)
+    }
+}
+
+// (x, y) の対応表を x について線形補間する（範囲外は端の値。x は昇順）
+fn interpolate(table: &[(f64, f64)], x: f64) -> f64 {
+    let Some(&(first_x, first_y)) = table.first() else {
+        return 0.0;
+    };
+    if x <= first_x {
+        return first_y;
+    }
+    for pair in table.windows(2) {
+        let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+        if x <= x1 {
+            return if x1 > x0 { y0 + (y1 - y0) * (x - x0) / (x1 - x0) } else { y0 };
+        }
+    }
+    table[table.len() - 1].1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+
+    const DIFFICULTIES: [AiDifficulty; 4] = [AiDifficulty::Easy, AiDifficulty::Medium, AiDifficulty::Hard, AiDifficulty::Expert];
+
+    // 0〜100 を 0.5 刻みで
+    fn strengths() -> impl Iterator<Item = Strength> {
+        (0..=200).map(|step| Strength::new(step as f64 / 2.0))
+    }
+
+    fn depth(strategy: Strategy) -> u8 {
+        match strategy {
+            Strategy::Greedy => 0,
+            Strategy::Minimax(depth) | Strategy::AlphaBeta(depth) => depth,
+        }
+    }
+
+    #[test]
+    fn elo_round_trips() {
+        for strength in strengths() {
+            let back = Strength::from_elo(strength.elo());
+            assert!((back.value() - strength.value()).abs() < 1e-9, "{:?} -> {:?}", strength, back);
+        }
+        assert_eq!(Strength::from_elo(-100.0), Strength::new(0.0));
+        assert_eq!(Strength::from_elo(1e6), Strength::new(100.0));
+    }
+
+    #[test]
+    fn difficulties_play_without_noise() {
+        for difficulty in DIFFICULTIES {
+            let config = Strength::from(difficulty).config();
+            assert_eq!(depth(config.strategy), depth(difficulty.strategy()), "{:?}", difficulty);
+            assert_eq!(config.noise, 0, "{:?}", difficulty);
+            assert_eq!(config.blunder, 0.0, "{:?}", difficulty);
+        }
+
+        // 中級はミニマックス法、強さの設定はアルファベータ法だが、ルートの各手の評価値は同じになる
+        let mut game = Game::new();
+        game.play_transcript("f5d6c3d3c4f4").unwrap();
+        assert_eq!(
+            game.evaluate_moves_with(AiDifficulty::Medium.strategy()),
+            game.evaluate_moves_with(AiDifficulty::Medium.strength().config())
+        );
+    }
+
+    #[test]
+    fn noise_and_blunder_weaken_monotonically() {
+        let configs: Vec<AiConfig> = strengths().map(Strength::config).collect();
+        for config in &configs {
+            assert!((0..=MAX_NOISE as i32).contains(&config.noise), "{:?}", config);
+            assert!((0.0..=MAX_BLUNDER).contains(&config.blunder), "{:?}", config);
+        }
+        for pair in configs.windows(2) {
+            // 悪手の確率は強さとともに減り、乱数の幅は同じ深さの間では強さとともに減る
+            assert!(pair[1].blunder <= pair[0].blunder);
+            if depth(pair[0].strategy) == depth(pair[1].strategy) {
+                assert!(pair[1].noise <= pair[0].noise, "{:?} -> {:?}", pair[0], pair[1]);
+            } else {
+                assert!(depth(pair[1].strategy) > depth(pair[0].strategy));
+            }
+        }
+        assert_eq!((configs[0].noise, configs[0].blunder), (MAX_NOISE as i32, MAX_BLUNDER));
+    }
+
+    #[test]
+    fn out_of_range_strengths_are_clamped() {
+        assert_eq!(Strength::new(f64::NAN).value(), 0.0);
+        assert_eq!(Strength::new(-5.0).value(), 0.0);
+        assert_eq!(Strength::new(150.0).value(), 100.0);
+        assert_eq!(Strength::new(f64::INFINITY).value(), 100.0);
+        assert_eq!(Strength::new(150.0).config(), Strength::new(100.0).config());
+    }
+}
@@ -0,0 +1,364 @@
+// AI 同士の総当たり戦と結果の集計
+//
+// 評価の拮抗した開始局面を複数用意し、各組み合わせで先手・後手を入れ替えて対局させる。
+// 勝率・石差に加えて Elo レーティングの推定値と 95% 信頼区間を求め、
+// AI の変更が本当に強くなっているかを確かめるために使う。
+
+use std::collections::HashSet;
+
+use crate::ai::{AiConfig, Strategy};
+use crate::game::Game;
+
+// 開始局面の評価に使う探索の深さ
+const OPENING_SEARCH_DEPTH: u8 = 4;
+
+// 総当たり戦の参加者
+#[derive(Debug, Clone)]
+pub struct Entrant {
+    pub name: String,
+    pub config: AiConfig,
+}
+
+// 1局の結果
+#[derive(Debug, Clone)]
+pub struct GameRecord {
+    pub black: usize, // 黒番の参加者の番号
+    pub white: usize, // 白番の参加者の番号
+    pub opening: usize,
+    pub black_discs: i32,
+    pub white_discs: i32,
+    pub transcript: String, // 初期配置からの棋譜（開始局面までの手を含む）
+}
+
+impl GameRecord {
+    // 指定した参加者から見た得点（勝ち 1, 引き分け 0.5, 負け 0）と石差。対局していない場合は None
+    pub fn result_for(&self, entrant: usize) -> Option<(f64, i32)> {
+        let diff = if entrant == self.black {
+            self.black_discs - self.white_discs
+        } else if entrant == self.white {
+            self.white_discs - self.black_discs
+        } else {
+            return None;
+        };
+        let score = match diff.signum() {
+            1 => 1.0,
+            0 => 0.5,
+            _ => 0.0,
+        };
+        Some((score, diff))
+    }
+}
+
+// 参加者ごとの成績
+#[derive(Debug, Clone, Default)]
+pub struct Standing {
+    pub games: usize,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    pub disc_diff: i32, // 石差の合計
+    pub scores: Vec<f64>, // 各対局の得点
+}
+
+impl Standing {
+    // 勝率（引き分けは0.5勝として数える）
+    pub fn win_rate(&self) -> f64 {
+        mean(&self.scores)
+    }
+
+    // 1局あたりの平均石差
+    pub fn average_disc_diff(&self) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            self.disc_diff as f64 / self.games as f64
+        }
+    }
+}
+
+// Elo レーティング差の推定値と 95% 信頼区間
+#[derive(Debug, Clone, Copy)]
+pub struct EloEstimate {
+    pub elo: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl EloEstimate {
+    // 各対局の得点から推定する（得点の平均を勝率とみなし、正規近似で信頼区間を求める）
+    pub fn from_scores(scores: &[f64]) -> Self {
+        let n = scores.len().max(1) as f64;
+        let p = mean(scores);
+        let variance = scores.iter().map(|s| (s - p).powi(2)).sum::<f64>() / n;
+        let margin = 1.96 * (variance / n).sqrt();
+        EloEstimate {
+            elo: elo_difference(p),
+            lower: elo_difference(p - margin),
+            upper: elo_difference(p + margin),
+        }
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.5
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
+
+// 勝率から Elo レーティング差を求める（全勝・全敗でも有限の値になるよう勝率を制限する）
+pub fn elo_difference(win_rate: f64) -> f64 {
+    let p = win_rate.clamp(0.001, 0.999);
+    -400.0 * (1.0 / p - 1.0).log10()
+}
+
+// 盤面を8通りの対称変換のうち辞書順で最小の文字列にする（対称な開始局面を1つにまとめるため）
+fn canonical_position(game: &Game) -> String {
+    let board = game.board();
+    (0..8)
+        .map(|symmetry| {
+            let mut text: String = (0..64)
+                .map(|index| {
+                    let (mut row, mut col) = (index / 8, index % 8);
+                    if symmetry & 1 != 0 {
+                        col = 7 - col;
+                    }
+                    if symmetry & 2 != 0 {
+                        row = 7 - row;
+                    }
+                    if symmetry & 4 != 0 {
+                        std::mem::swap(&mut row, &mut col);
+                    }
+                    match board[row][col] {
+                        1 => 'X',
+                        2 => 'O',
+                        _ => '-',
+                    }
+                })
+                .collect();
+            text.push(if game.current_player() == 1 { 'X' } else { 'O' });
+            text
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+// 初期配置から plies 手進めた局面のうち、評価が最も拮抗している局面を count 個選ぶ
+pub fn balanced_openings(plies: usize, count: usize) -> Vec<Game> {
+    let mut positions = vec![Game::new()];
+    for _ in 0..plies {
+        let mut seen = HashSet::new();
+        let mut next = Vec::new();
+        for game in &positions {
+            for (row, col) in game.valid_moves() {
+                let mut child = game.clone();
+                if child.play(row, col).is_ok() && seen.insert(canonical_position(&child)) {
+                    next.push(child);
+                }
+            }
+        }
+        positions = next;
+    }
+
+    // 手番のプレイヤーから見た最善手の評価値の絶対値が小さい順（同じ場合は棋譜順）
+    let mut rated: Vec<(i32, Game)> = positions
+        .into_iter()
+        .filter(|game| !game.is_game_over())
+        .map(|game| {
+            let evaluations = game.evaluate_moves_with(Strategy::AlphaBeta(OPENING_SEARCH_DEPTH));
+            let best = evaluations.iter().map(|&(_, score)| score).max().unwrap_or(0);
+            (best.abs(), game)
+        })
+        .collect();
+    rated.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.transcript().cmp(&b.1.transcript())));
+    rated.into_iter().take(count).map(|(_, game)| game).collect()
+}
+
+// 開始局面から終局まで対局させる
+pub fn play_game(opening: &Game, black: &AiConfig, white: &AiConfig) -> Game {
+    let mut game = opening.clone();
+    while !game.is_game_over() {
+        let config = if game.current_player() == 1 { black } else { white };
+        let Some((row, col)) = game.ai_move_with(*config) else {
+            break;
+        };
+        if game.play(row, col).is_err() {
+            break;
+        }
+    }
+    game
+}
+
+// 総当たり戦を行う（各組み合わせ・各開始局面で先手と後手を入れ替えて2局ずつ）。
+// on_game は1局終わるごとに呼ばれる（進捗の表示用）
+pub fn round_robin(entrants: &[Entrant], openings: &[Game], mut on_game: impl FnMut(&GameRecord)) -> Vec<GameRecord> {
+    let mut records = Vec::new();
+    for first in 0..entrants.len() {
+        for second in first + 1..entrants.len() {
+            for (index, opening) in openings.iter().enumerate() {
+                for (black, white) in [(first, second), (second, first)] {
+                    let game = play_game(opening, &entrants[black].config, &entrants[white].config);
+                    let (black_discs, white_discs) = game.score();
+                    let record = GameRecord {
+                        black,
+                        white,
+                        opening: index,
+                        black_discs,
+                        white_discs,
+                        transcript: game.transcript(),
+                    };
+                    on_game(&record);
+                    records.push(record);
+                }
+            }
+        }
+    }
+    records
+}
+
+// 参加者ごとの成績を集計
+pub fn standings(entrant_count: usize, records: &[GameRecord]) -> Vec<Standing> {
+    let mut standings = vec![Standing::default(); entrant_count];
+    for record in records {
+        for (entrant, standing) in standings.iter_mut().enumerate() {
+            let Some((score, diff)) = record.result_for(entrant) else {
+                continue;
+            };
+            standing.games += 1;
+            standing.disc_diff += diff;
+            standing.scores.push(score);
+            match diff.signum() {
+                1 => standing.wins += 1,
+                0 => standing.draws += 1,
+                _ => standing.losses += 1,
+            }
+        }
+    }
+    standings
+}
+
+// 2人の参加者の直接対決での first から見た得点
+pub fn head_to_head(first: usize, second: usize, records: &[GameRecord]) -> Vec<f64> {
+    records
+        .iter()
+        .filter(|record| record.result_for(second).is_some())
+        .filter_map(|record| record.result_for(first).map(|(score, _)| score))
+        .collect()
+}
+
+// 全対局の結果から各参加者のレーティングを最尤推定する（Bradley-Terry モデル、最初の参加者を 0 とする）。
+// 全勝・全敗でも有限の値になるよう、各組み合わせに引き分け1局分を加えて推定する
+pub fn fit_ratings(entrant_count: usize, records: &[GameRecord]) -> Vec<f64> {
+    let mut games = vec![vec![1.0; entrant_count]; entrant_count];
+    let mut scores = vec![0.5 * entrant_count.saturating_sub(1) as f64; entrant_count];
+    for record in records {
+        games[record.black][record.white] += 1.0;
+        games[record.white][record.black] += 1.0;
+        if let (Some((black_score, _)), Some((white_score, _))) = (record.result_for(record.black), record.result_for(record.white)) {
+            scores[record.black] += black_score;
+            scores[record.white] += white_score;
+        }
+    }
+
+    // MM アルゴリズムで強さ γ を反復的に求める
+    let mut gamma = vec![1.0; entrant_count];
+    for _ in 0..1000 {
+        let next: Vec<f64> = (0..entrant_count)
+            .map(|i| {
+                let denominator: f64 = (0..entrant_count)
+                    .filter(|&j| j != i)
+                    .map(|j| games[i][j] / (gamma[i] + gamma[j]))
+                    .sum();
+                if denominator > 0.0 { scores[i] / denominator } else { gamma[i] }
+            })
+            .collect();
+        let change = next.iter().zip(&gamma).map(|(a, b)| (a / b).ln().abs()).fold(0.0, f64::max);
+        gamma = next;
+        if change < 1e-9 {
+            break;
+        }
+    }
+
+    let base = gamma.first().copied().unwrap_or(1.0);
+    gamma.iter().map(|g| 400.0 * (g / base).log10()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(black: usize, white: usize, black_discs: i32, white_discs: i32) -> GameRecord {
+        GameRecord {
+            black,
+            white,
+            opening: 0,
+            black_discs,
+            white_discs,
+            transcript: String::new(),
+        }
+    }
+
+    #[test]
+    fn elo_difference_of_known_win_rates() {
+        assert_eq!(elo_difference(0.5), 0.0);
+        assert!((elo_difference(0.75) - 190.85).abs() < 0.01);
+        assert!((elo_difference(0.25) + 190.85).abs() < 0.01);
+        // 全勝・全敗でも有限の値
+        assert!(elo_difference(1.0).is_finite() && elo_difference(0.0).is_finite());
+    }
+
+    #[test]
+    fn confidence_interval_contains_the_estimate() {
+        let scores = [1.0, 1.0, 0.5, 0.0, 1.0, 1.0, 0.0, 1.0];
+        let estimate = EloEstimate::from_scores(&scores);
+        assert!((estimate.elo - elo_difference(0.6875)).abs() < 1e-9);
+        assert!(estimate.lower < estimate.elo && estimate.elo < estimate.upper);
+
+        // 結果がすべて同じなら幅はない
+        let estimate = EloEstimate::from_scores(&[0.5; 4]);
+        assert_eq!((estimate.lower, estimate.elo, estimate.upper), (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn standings_count_results_from_both_sides() {
+        let records = [record(0, 1, 40, 24), record(1, 0, 32, 32), record(1, 2, 20, 44), record(2, 0, 10, 54)];
+        let standings = standings(3, &records);
+
+        let first = &standings[0];
+        assert_eq!((first.games, first.wins, first.draws, first.losses, first.disc_diff), (3, 2, 1, 0, 60));
+        assert_eq!(first.win_rate(), 2.5 / 3.0);
+        assert_eq!(first.average_disc_diff(), 20.0);
+        let second = &standings[1];
+        assert_eq!((second.games, second.wins, second.draws, second.losses, second.disc_diff), (3, 0, 1, 2, -40));
+        let third = &standings[2];
+        assert_eq!((third.games, third.wins, third.losses, third.disc_diff), (2, 1, 1, -20));
+
+        assert_eq!(head_to_head(0, 1, &records), vec![1.0, 0.5]);
+        assert_eq!(head_to_head(2, 0, &records), vec![0.0]);
+    }
+
+    #[test]
+    fn fit_ratings_recovers_known_ratings() {
+        // レーティング 0, 150, 400 の参加者が期待勝率どおりに勝った 1000 局ずつの結果
+        let ratings = [0.0, 150.0, 400.0];
+        let mut records = Vec::new();
+        for first in 0..ratings.len() {
+            for second in first + 1..ratings.len() {
+                let expected = 1.0 / (1.0 + 10f64.powf((ratings[first] - ratings[second]) / 400.0));
+                let second_wins = (1000.0 * expected).round() as usize;
+                for game in 0..1000 {
+                    let (black, white) = if game % 2 == 0 { (first, second) } else { (second, first) };
+                    let (black_discs, white_discs) = if (game < second_wins) == (black == second) { (40, 24) } else { (24, 40) };
+                    records.push(record(black, white, black_discs, white_discs));
+                }
+            }
+        }
+
+        let fitted = fit_ratings(ratings.len(), &records);
+        assert_eq!(fitted[0], 0.0);
+        for (fitted, expected) in fitted.iter().zip(ratings) {
+            assert!((fitted - expected).abs() < 5.0, "{} != {}", fitted, expected);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+// 置換表
+//
+// 同じ局面を別の手順で再び探索したときに結果を再利用する。
+// キーとデータを XOR して格納するロックフリー実装のため、並列探索の
+// 複数スレッドからそのまま共有できる（書き込みが競合した項目は読み出し時に捨てられる）。
+// 1 枚の葉しか評価しない探索も多いため、領域は最初に格納するときに確保する。
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+// 評価値の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Bound {
+    Exact, // 正確な値
+    Lower, // 下限（ベータカットが起きた）
+    Upper, // 上限（どの手もアルファを超えなかった）
+}
+
+// 置換表の1項目
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TableEntry {
+    pub(crate) depth: u8,
+    pub(crate) value: i32,
+    pub(crate) bound: Bound,
+}
+
+struct Slot {
+    key: AtomicU64,
+    data: AtomicU64,
+}
+
+pub(crate) struct TranspositionTable {
+    bits: u32,
+    slots: OnceLock<Vec<Slot>>, // 最初の store で確保する
+}
+
+impl TranspositionTable {
+    // 2^bits 個の項目を持つ置換表を作成
+    pub(crate) fn new(bits: u32) -> Self {
+        TranspositionTable { bits, slots: OnceLock::new() }
+    }
+
+    fn slot(slots: &[Slot], hash: u64) -> &Slot {
+        &slots[(hash as usize) & (slots.len() - 1)]
+    }
+
+    // 局面のハッシュ値で項目を検索
+    pub(crate) fn probe(&self, hash: u64) -> Option<TableEntry> {
+        let slot = Self::slot(self.slots.get()?, hash);
+        let data = slot.data.load(Ordering::Relaxed);
+        if slot.key.load(Ordering::Relaxed) ^ data != hash || data == 0 {
+            return None;
+        }
+
+        let bound = match (data >> 40) & 0xFF {
+            1 => Bound::Exact,
+            2 => Bound::Lower,
+            3 => Bound::Upper,
+            _ => return None,
+        };
+        Some(TableEntry {
+            depth: ((data >> 32) & 0xFF) as u8,
+            value: data as u32 as i32,
+            bound,
+        })
+    }
+
+    // 項目を格納（常に上書きする）
+    pub(crate) fn store(&self, hash: u64, entry: TableEntry) {
+        let bound = match entry.bound {
+            Bound::Exact => 1u64,
+            Bound::Lower => 2,
+            Bound::Upper => 3,
+        };
+        let data = (entry.value as u32 as u64) | ((entry.depth as u64) << 32) | (bound << 40);
+
+        let slots = self.slots.get_or_init(|| {
+            (0..1usize << self.bits)
+                .map(|_| Slot {
+                    key: AtomicU64::new(0),
+                    data: AtomicU64::new(0),
+                })
+                .collect()
+        });
+        let slot = Self::slot(slots, hash);
+        slot.key.store(hash ^ data, Ordering::Relaxed);
+        slot.data.store(data, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,94 @@
+// Web Worker 向けの非同期探索API
+//
+// Canvas を持たない Worker 内で局面文字列を受け取って探索し、結果を Promise で返す。
+// 一定のノード数を探索するごとにイベントループへ制御を返すため、探索中でも
+// キャンセルのメッセージを受け取ることができる。
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use js_sys::{Function, Int32Array, Promise, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{future_to_promise, JsFuture};
+
+use crate::ai::{AiConfig, AiDifficulty};
+use crate::board::BoardState;
+use crate::incremental::{self, SteppedSearch};
+
+// イベントループへ制御を返すまでに探索するノード数
+const SLICE_NODES: u32 = 10_000;
+
+// 探索のキャンセル用トークン
+#[wasm_bindgen]
+#[derive(Clone, Default)]
+pub struct SearchCancelToken {
+    cancelled: Rc<Cell<bool>>,
+}
+
+#[wasm_bindgen]
+impl SearchCancelToken {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> SearchCancelToken {
+        SearchCancelToken::default()
+    }
+
+    // 探索をキャンセル（次にイベントループへ制御が戻った時点で Promise が reject される）
+    pub fn cancel(&self) {
+        self.cancelled.set(true);
+    }
+
+    // キャンセルされたかどうかを取得
+    #[wasm_bindgen(getter)]
+    pub fn cancelled(&self) -> bool {
+        self.cancelled.get()
+    }
+}
+
+// 局面文字列を探索し、最善手 [行, 列] で resolve する Promise を返す（有効な手がない場合は [-1, -1]）
+#[wasm_bindgen]
+pub fn search_position(position: &str, difficulty: AiDifficulty, token: &SearchCancelToken) -> Result<Promise, JsValue> {
+    search_with(position, difficulty.into(), token)
+}
+
+// AiConfig の形式の設定文字列（OthelloGame.ai_config の値）を指定して search_position と同じ探索を行う
+#[wasm_bindgen]
+pub fn search_position_with_config(position: &str, config: &str, token: &SearchCancelToken) -> Result<Promise, JsValue> {
+    let config = incremental::parse_config(config).map_err(|e| JsValue::from_str(&e))?;
+    search_with(position, config, token)
+}
+
+fn search_with(position: &str, config: AiConfig, token: &SearchCancelToken) -> Result<Promise, JsValue> {
+    let (board, player) = BoardState::from_position_string(position).map_err(|e| JsValue::from_str(&e))?;
+    let cancelled = token.cancelled.clone();
+
+    Ok(future_to_promise(async move {
+        let mut search = SteppedSearch::new(board, player, config);
+        loop {
+            // 一定のノード数ごとにイベントループへ制御を返し、キャンセルを受け付ける
+            yield_to_event_loop().await?;
+            if cancelled.get() {
+                return Err(JsValue::from_str("cancelled"));
+            }
+            if search.step(SLICE_NODES) {
+                break;
+            }
+        }
+
+        let best_move = match search.best_move() {
+            Some((row, col)) => [row as i32, col as i32],
+            None => [-1, -1],
+        };
+        Ok(Int32Array::from(&best_move[..]).into())
+    }))
+}
+
+// setTimeout(0) でイベントループへ制御を返す（Worker でもメインスレッドでも動作する）
+async fn yield_to_event_loop() -> Result<(), JsValue> {
+    let global = js_sys::global();
+    let set_timeout: Function = Reflect::get(&global, &JsValue::from_str("setTimeout"))?.dyn_into()?;
+    let promise = Promise::new(&mut |resolve, _reject| {
+        let _ = set_timeout.call2(&global, &resolve, &JsValue::from(0));
+    });
+    JsFuture::from(promise).await.map(|_| ())
+}
@@ -0,0 +1,68 @@
+// gtp コマンドを標準入出力から操作するテスト
+//
+// 初級の AI（貪欲法）で着手・取り消し・不正な座標・パスへの応答（"=" / "?"）と、
+// genmove の手を並べた棋譜が終局まで再生できることを確かめる。
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use mini_wasm_othello::Game;
+
+// 標準入力を渡して実行し、応答（空行で区切られたもの）を返す
+fn run(input: &str) -> Vec<String> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_gtp"))
+        .args(["--difficulty", "easy"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("gtp を起動できません");
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    stdout.split_terminator("\n\n").map(str::to_string).collect()
+}
+
+#[test]
+fn replies_to_moves_undo_and_bad_input() {
+    let replies = run("play b f5\ngenmove w\n3 play w z9\nplay b d9\nplay w f5\nplay b pass\nundo\nundo\nundo\n7 showboard\nquit\n");
+    assert_eq!(
+        replies[..9],
+        [
+            "=",
+            "= f4",
+            "?3 invalid coordinate",
+            "? invalid coordinate",
+            "? illegal move",
+            "? illegal move",
+            "=",
+            "=",
+            "? cannot undo",
+        ]
+    );
+    assert!(replies[9].starts_with("=7 \n") && replies[9].ends_with("X: 2  O: 2"), "{}", replies[9]);
+    assert_eq!(replies[10], "=");
+}
+
+#[test]
+fn genmove_plays_a_full_game_with_passes() {
+    let input = "genmove b\ngenmove w\n".repeat(40) + "play b pass\nplay w pass\nfinal_score\n";
+    let replies = run(&input);
+    assert!(replies.iter().all(|reply| reply.starts_with('=')), "{:?}", replies);
+
+    // 終局後はどちらの色もパスになる
+    let moves: Vec<&str> = replies[..80].iter().map(|reply| reply.trim_start_matches('=').trim()).collect();
+    assert_eq!(moves[78..], ["pass", "pass"]);
+
+    let transcript: String = moves.iter().filter(|&&mv| mv != "pass").copied().collect();
+    let mut game = Game::new();
+    game.play_transcript(&transcript).unwrap();
+    assert!(game.is_game_over());
+    let (black, white) = game.score();
+    let expected = match black.cmp(&white) {
+        std::cmp::Ordering::Greater => format!("= B+{}", black - white),
+        std::cmp::Ordering::Less => format!("= W+{}", white - black),
+        std::cmp::Ordering::Equal => "= 0".to_string(),
+    };
+    assert_eq!(replies[82], expected);
+}
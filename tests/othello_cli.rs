@@ -0,0 +1,51 @@
+// 端末版（othello コマンド）を標準入出力から操作するテスト
+//
+// 乱数を使わない初級の AI（--no-variety）と対局し、着手・取り消し・不正な入力・パス・棋譜の保存を確かめる。
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// 引数と標準入力を渡して実行し、標準出力を返す
+fn run(args: &[&str], input: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_othello"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("othello を起動できません");
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn plays_undoes_and_rejects_bad_input() {
+    let path = std::env::temp_dir().join(format!("othello-cli-{}.txt", std::process::id()));
+    let input = format!("f5\ntranscript\nz9\nundo\nundo\ntranscript\nd3\nd3\nsave {}\nquit\n", path.display());
+    let output = run(&["--difficulty", "easy", "--no-variety"], &input);
+
+    // 初級は f5 に対して f4 を返す
+    assert!(output.contains("AI 白 (O): f4\n"), "{}", output);
+    assert!(output.contains("> f5f4\n"), "{}", output);
+    assert!(output.contains("不明なコマンドです: z9"), "{}", output);
+    // 1回目の undo で AI の手ごと初期局面に戻り、2回目は取り消せない
+    assert!(output.contains("取り消せる手がありません"), "{}", output);
+    assert!(output.contains("> \n>"), "{}", output);
+    assert!(output.contains("AI 白 (O): c3\n"), "{}", output);
+    assert!(output.contains("d3 には置けません"), "{}", output);
+
+    let saved = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(saved, "d3c3\n");
+}
+
+#[test]
+fn ai_passes_without_moves() {
+    // c8 に打つと白は打てる手がなく、AI は打たずに黒の番に戻る
+    let position = "OOOOOOOX--OOOOOX-OOOOXXX--OXXOXXOOOOOOOX-OXOXOXX-OOXXXXXXO-OXXOO X";
+    let output = run(&["--difficulty", "easy", "--no-variety", "--position", position], "c8\ntranscript\nquit\n");
+    assert!(!output.contains("AI 白 (O)"), "{}", output);
+    assert!(output.contains("黒 (X): 26  白 (O): 31\n黒 (X) の番です"), "{}", output);
+    assert!(output.contains("> c8\n"), "{}", output);
+}
@@ -0,0 +1,80 @@
+// perft による指し手生成の検証
+//
+// 初期局面からの値は公開されている値と比べる。深さ 9 以上は時間がかかるため
+// `cargo test --release -- --ignored` で実行する。
+
+use mini_wasm_othello::perft::{divide, perft, START_PERFT};
+use mini_wasm_othello::Game;
+
+fn check_start(depth: usize) {
+    assert_eq!(perft(&Game::new(), depth as u32), START_PERFT[depth], "深さ {}", depth);
+}
+
+#[test]
+fn start_position_shallow() {
+    for depth in 0..=8 {
+        check_start(depth);
+    }
+}
+
+#[test]
+#[ignore]
+fn start_position_depth_9() {
+    check_start(9);
+}
+
+#[test]
+#[ignore]
+fn start_position_depth_10() {
+    check_start(10);
+}
+
+#[test]
+#[ignore]
+fn start_position_depth_11() {
+    check_start(11);
+}
+
+#[test]
+fn divide_sums_to_perft() {
+    let game = Game::new();
+    for depth in 1..=6 {
+        let total: u64 = divide(&game, depth).iter().map(|&(_, nodes)| nodes).sum();
+        assert_eq!(total, START_PERFT[depth as usize]);
+    }
+}
+
+#[test]
+fn first_moves_are_symmetric() {
+    // 初期局面の4つの手は対称なので、どの手の後も perft は初期局面の 1/4 になる
+    for first in ["f5", "d3", "c4", "e6"] {
+        let mut game = Game::new();
+        game.play_transcript(first).unwrap();
+        for depth in 0..=6 {
+            assert_eq!(perft(&game, depth as u32) * 4, START_PERFT[depth + 1], "{} の後の深さ {}", first, depth);
+        }
+    }
+}
+
+// 局面文字列の盤面を時計回りに 90 度回転
+fn rotate(position: &str) -> String {
+    let squares: Vec<char> = position.chars().take(64).collect();
+    let mut rotated: String = (0..64).map(|index| squares[(7 - index % 8) * 8 + index / 8]).collect();
+    rotated.push_str(&position[64..]);
+    rotated
+}
+
+#[test]
+fn rotated_positions_have_equal_perft() {
+    for transcript in ["f5d6c3d3c4f4c5b3c2", "f5f6e6f4e3c5c4", "f5d6c5f4e3c6d3f6e6d7", "c4e3f6e6f5c5f4g6f7"] {
+        let mut game = Game::new();
+        game.play_transcript(transcript).unwrap();
+        let expected = perft(&game, 4);
+
+        let mut position = game.position();
+        for _ in 0..3 {
+            position = rotate(&position);
+            assert_eq!(perft(&Game::from_position(&position).unwrap(), 4), expected, "{} を回転: {}", transcript, position);
+        }
+    }
+}
@@ -0,0 +1,150 @@
+// AI が最善手のわかっている局面で正しい手を選ぶことを確かめる回帰テスト
+//
+// 終盤の局面は完全読みで最善手が1つに決まり、次善手より 4 石以上良いものを選んでいる。
+// 終局まで読める深さで探索するため、評価値の視点（手番・パス）を取り違えると別の手を選ぶ。
+
+use mini_wasm_othello::game::{parse_square, square_name};
+use mini_wasm_othello::{AiConfig, Game};
+
+// (説明, 局面文字列, 最善手)
+const ENDGAME_POSITIONS: &[(&str, &str, &str)] = &[
+    (
+        "a1 の角を取って勝つ（4 空き）",
+        "-XXXXXXXOOOOOOOOXOOXOOXOXXOOXOOOXXOOOXOOXXOXOOXOX-OOOOOO-XXXXX-O X",
+        "a1",
+    ),
+    (
+        "a6 で +20（4 空き）",
+        "OOXXXXXXXXXXXXXXXXXOOOXXXXXOOOXXOXOOXOOX-OOOOXOXOXOOO-XXOXOO-O-X X",
+        "a6",
+    ),
+    (
+        "白番で b7 なら +52（4 空き）",
+        "OOOOOOOOOOOOOOOOOXOOOOOOOOXOOOOOOXOOOXOOOXXOOOXO--XXOOOO--XXXOOO O",
+        "b7",
+    ),
+    (
+        "a2 で +10（6 空き）",
+        "-XXXXXXX-OXOXXXX-XOXXOXXXOXOOXOXOOOXOXOX-OOOXOOX-OOOOOOXOOOOOOO- X",
+        "a2",
+    ),
+    (
+        "負けを 2 石差に抑える a1（6 空き）",
+        "--XO-OOOXOOO-OO-XOOOXXXXXOXOOXXXXOXOXXO-XXXXXOOOXXXXXXOOXOOOOOOO X",
+        "a1",
+    ),
+    (
+        "白番で f6 なら +10（7 空き）",
+        "OOOOOOXXOOOOOOOXOOOOOOOXOXXXXOOXOOXXOOOOOOXXX---XXXOOX--OXXOO-X- O",
+        "f6",
+    ),
+    (
+        "白番で d7 なら -10 に抑えられる（7 空き）",
+        "XXXXXO--XOOXXO-OXOXXXXOOXXOOOOOOXOOXXXOOXXOXOXOOX-X-OOOO-XXXXX-O O",
+        "d7",
+    ),
+    (
+        "a3 なら +10、他は負け（8 空き）",
+        "-X-XXXXX--XXXOOO-OXXOXOOOOOXXXOOXOOXXOXOXXOXOOOOXXXO-OOOXXO--OOO X",
+        "a3",
+    ),
+    (
+        "X マスの b2 が最善（8 空き）",
+        "OOOOOOOOX-XOOXOOXXOOOOOOXXOXXOOOXXOXXOOOXXOXOXXO-XXXX-X---OXXX-- X",
+        "b2",
+    ),
+    (
+        "b8 で引き分けに持ち込む（8 空き）",
+        "OOO-XXX-OOOOOX--OOOOXOX-OOOOOOOOOOXXOOOOOOXOOOX-OXOOXXX-X-XOXXXO X",
+        "b8",
+    ),
+    (
+        "c8 で相手をパスさせて +28（8 空き）",
+        "OOOOOOOX--OOOOOX-OOOOXXX--OXXOXXOOOOOOOX-OXOXOXX-OOXXXXXXO-OXXOO X",
+        "c8",
+    ),
+    (
+        "白番で b8 なら相手がパスして +30（9 空き）",
+        "-OOO---X--XXXXXOXXXXOOOO-XOOXOXXXXOXOXXXOOXOXXXXOXXXXXX-X-XXXXXX O",
+        "b8",
+    ),
+];
+
+fn best_move(position: &str, config: &str) -> String {
+    let game = Game::from_position(position).unwrap();
+    let config: AiConfig = config.parse().unwrap();
+    let (row, col) = game.ai_move_with(config).expect("有効な手がありません");
+    square_name(row, col)
+}
+
+// 空きマスの数（パスで深さを消費しても終局まで届くよう、探索の深さに余裕を持たせる）
+fn empties(position: &str) -> usize {
+    position.chars().take(64).filter(|&c| c == '-').count()
+}
+
+#[test]
+fn finds_exact_endgame_moves() {
+    for &(description, position, expected) in ENDGAME_POSITIONS {
+        let config = format!("depth={}", empties(position) + 2);
+        assert_eq!(best_move(position, &config), expected, "{}: {}", description, position);
+    }
+}
+
+#[test]
+fn incremental_search_agrees_with_recursive_search() {
+    use mini_wasm_othello::incremental::IncrementalSearch;
+
+    for &(description, position, expected) in ENDGAME_POSITIONS {
+        let config = format!("depth={}", empties(position) + 2);
+        let Ok(mut search) = IncrementalSearch::with_config(position, &config) else {
+            panic!("探索を作成できません: {}", position);
+        };
+        while !search.step(10_000) {}
+        let best = search.best_move();
+        assert_eq!(square_name(best[0] as usize, best[1] as usize), expected, "{}: {}", description, position);
+    }
+}
+
+#[test]
+fn wipes_out_the_opponent() {
+    // f4 なら白の石をすべて返して勝ち、e5 は d4 を返すだけ
+    let position = concat!(
+        "--------",
+        "--------",
+        "--X-----",
+        "--XOO---",
+        "--------",
+        "--------",
+        "--------",
+        "-------- X",
+    );
+    for config in ["depth=1", "depth=3", "depth=4", "minimax=3"] {
+        assert_eq!(best_move(position, config), "f4", "{}", config);
+    }
+}
+
+#[test]
+fn same_move_for_both_colours() {
+    // 色を入れ替えた局面でも同じ手を選ぶ（評価が手番の色に依存しない）
+    for &(description, position, expected) in ENDGAME_POSITIONS {
+        let swapped: String = position
+            .chars()
+            .map(|c| match c {
+                'X' => 'O',
+                'O' => 'X',
+                other => other,
+            })
+            .collect();
+        let config = format!("depth={}", empties(position) + 2);
+        assert_eq!(best_move(&swapped, &config), expected, "{}（色を入れ替え）: {}", description, swapped);
+    }
+}
+
+#[test]
+fn expected_moves_are_legal() {
+    for &(description, position, expected) in ENDGAME_POSITIONS {
+        let game = Game::from_position(position).unwrap();
+        let square = parse_square(expected).unwrap();
+        assert!(game.valid_moves().contains(&square), "{}: {} は有効な手ではありません", description, expected);
+    }
+}
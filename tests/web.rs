@@ -0,0 +1,270 @@
+// OthelloGame（wasm の公開 API）のテスト
+//
+// Canvas なしで作成したゲームで、クリック・AI の手・スコア・終局判定を確かめる。
+// ネイティブでは `cargo test`、wasm では `wasm-pack test --node`
+// （または `cargo test --target wasm32-unknown-unknown --test web`）で同じテストを実行する。
+
+use mini_wasm_othello::game::parse_square;
+use mini_wasm_othello::review::review_transcript;
+use mini_wasm_othello::{AiConfig, AiDifficulty, Game, OthelloGame, Strategy};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+// Canvas なしの盤面は1辺 400 なので、1マスは 50
+const CELL: f64 = 50.0;
+
+// 盤面上のマスの中心の座標
+fn center(row: usize, col: usize) -> (f64, f64) {
+    (col as f64 * CELL + CELL / 2.0, row as f64 * CELL + CELL / 2.0)
+}
+
+// 棋譜のとおりに打つ
+fn play_transcript(game: &mut OthelloGame, transcript: &str) {
+    for index in (0..transcript.len()).step_by(2) {
+        let name = &transcript[index..index + 2];
+        let (row, col) = parse_square(name).unwrap();
+        assert!(game.play_move(row, col).unwrap(), "{} は有効な手ではありません", name);
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn starts_from_the_initial_position() {
+    let game = OthelloGame::headless();
+    assert_eq!(game.get_score(), vec![2, 2]);
+    assert_eq!(game.current_player(), 1);
+    assert_eq!(game.get_valid_moves_count(), 4);
+    assert!(!game.is_game_over());
+    assert_eq!(game.export_position(), Game::new().position());
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn click_places_a_disc() {
+    let mut game = OthelloGame::headless();
+    let (x, y) = center(4, 5);
+    assert!(game.click_at(x, y).unwrap());
+    assert_eq!(game.get_score(), vec![4, 1]);
+    assert_eq!(game.current_player(), 2);
+    assert_eq!(game.get_move_history(), vec![4, 5, 1]);
+
+    // 同じマスにもう一度は置けない
+    assert!(!game.click_at(x, y).unwrap());
+    assert_eq!(game.current_player(), 2);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn ignores_invalid_clicks() {
+    let mut game = OthelloGame::headless();
+    let (x, y) = center(0, 0);
+    assert!(!game.click_at(x, y).unwrap());
+    assert!(!game.click_at(-10.0, 10.0).unwrap());
+    assert!(!game.click_at(410.0, 10.0).unwrap());
+    assert_eq!(game.get_score(), vec![2, 2]);
+    assert_eq!(game.current_player(), 1);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn clicks_follow_the_view_rotation() {
+    let mut game = OthelloGame::headless();
+    game.set_view_rotation(1).unwrap();
+    game.set_view_mirrored(true).unwrap();
+    // f5 は左右反転で c5、時計回りに 90 度回転して表示上の 3 行 4 列目
+    let (x, y) = center(2, 3);
+    assert!(game.click_at(x, y).unwrap());
+    assert_eq!(game.get_move_history(), vec![4, 5, 1]);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn keyboard_moves_the_cursor_before_playing() {
+    let mut game = OthelloGame::headless();
+    assert!(!game.press_key("a").unwrap());
+    assert!(game.cursor().is_empty());
+
+    // 最初の Enter はカーソルを最初の有効手（d3）に表示するだけ
+    assert!(!game.press_key("Enter").unwrap());
+    assert_eq!(game.cursor(), vec![2, 3]);
+    assert_eq!(game.current_player(), 1);
+
+    assert!(!game.press_key("ArrowRight").unwrap());
+    assert_eq!(game.cursor(), vec![2, 4]);
+    // e3 には置けない
+    assert!(!game.press_key(" ").unwrap());
+    assert_eq!(game.current_player(), 1);
+
+    assert!(!game.press_key("ArrowLeft").unwrap());
+    assert!(game.press_key(" ").unwrap());
+    assert_eq!(game.get_move_history(), vec![2, 3, 1]);
+
+    // 盤の端ではそれ以上動かない
+    for _ in 0..3 {
+        game.press_key("ArrowUp").unwrap();
+    }
+    assert_eq!(game.cursor(), vec![0, 3]);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn arrow_keys_follow_the_view_rotation() {
+    let mut game = OthelloGame::headless();
+    game.set_view_rotation(1).unwrap();
+    game.press_key("ArrowUp").unwrap();
+    // 時計回りに 90 度回転した表示では、上は盤面の左
+    game.press_key("ArrowUp").unwrap();
+    assert_eq!(game.cursor(), vec![2, 2]);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn pointer_hovers_and_plays() {
+    let mut game = OthelloGame::headless();
+    let (x, y) = center(4, 5);
+    assert!(!game.pointer_at("pointermove", "touch", x, y).unwrap());
+    assert!(game.hover().is_empty());
+    assert!(!game.pointer_at("pointermove", "mouse", x, y).unwrap());
+    assert_eq!(game.hover(), vec![4, 5]);
+    assert!(!game.pointer_at("pointerleave", "mouse", x, y).unwrap());
+    assert!(game.hover().is_empty());
+
+    // ポインタで打つとキーボードカーソルは隠れる
+    game.press_key("Enter").unwrap();
+    assert!(!game.pointer_at("pointerup", "mouse", -10.0, y).unwrap());
+    assert!(game.pointer_at("pointerup", "touch", x, y).unwrap());
+    assert!(game.cursor().is_empty());
+    assert_eq!(game.get_move_history(), vec![4, 5, 1]);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn ai_plays_a_valid_move() {
+    let mut game = OthelloGame::headless();
+    play_transcript(&mut game, "f5");
+
+    let ai_move = game.get_ai_move();
+    let position = Game::from_position(&game.export_position()).unwrap();
+    assert!(position.valid_moves().contains(&(ai_move[0] as usize, ai_move[1] as usize)));
+
+    assert!(game.make_ai_move().unwrap());
+    assert_eq!(game.current_player(), 1);
+    assert_eq!(game.get_move_history()[3..], [ai_move[0], ai_move[1], 2]);
+    assert_eq!(game.get_score().iter().sum::<i32>(), 6);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn ai_plays_a_full_game() {
+    let mut game = OthelloGame::headless();
+    game.set_ai_difficulty(AiDifficulty::Easy);
+    while !game.is_game_over() {
+        if !game.make_ai_move().unwrap() {
+            assert!(game.pass().unwrap());
+        }
+    }
+
+    assert_eq!(game.get_valid_moves_count(), 0);
+    assert!(!game.pass().unwrap());
+    assert!(!game.make_ai_move().unwrap());
+
+    let score = game.get_score();
+    let discs = score.iter().sum::<i32>();
+    assert!(discs <= 64);
+    assert_eq!(discs as usize, 4 + game.get_move_history().len() / 3);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn game_over_after_wipeout() {
+    let mut game = OthelloGame::headless();
+    // 9手で白の石がなくなる最短の終局
+    play_transcript(&mut game, "d3c3b3d2e1d6d7e3f4");
+    assert!(game.is_game_over());
+    assert_eq!(game.get_score(), vec![13, 0]);
+    assert_eq!(game.get_valid_moves_count(), 0);
+    assert!(!game.pass().unwrap());
+    assert_eq!(game.get_ai_move(), vec![-1, -1]);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn passes_only_without_valid_moves() {
+    let mut game = OthelloGame::headless();
+    assert!(!game.pass().unwrap());
+    assert_eq!(game.current_player(), 1);
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn reviews_the_transcript() {
+    let mut game = OthelloGame::headless();
+    play_transcript(&mut game, "f5d6c3");
+    assert_eq!(game.get_transcript(), "f5d6c3");
+
+    // Web Worker では棋譜から振り返る。結果は OthelloGame.review と同じ
+    let report = review_transcript(&game.get_transcript()).unwrap();
+    let direct = game.review().unwrap();
+    assert_eq!(report.move_count(), 3);
+    assert_eq!(report.advantages().len(), 4);
+    assert_eq!(report.win_probabilities().len(), 4);
+    assert_eq!(report.advantages(), direct.advantages());
+    assert_eq!(report.summary(), direct.summary());
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn flip_preview_matches_the_discs_turned_over() {
+    let mut game = OthelloGame::headless();
+    play_transcript(&mut game, "f5d6c3d3c4f4c5b3c2");
+    let before = Game::from_position(&game.export_position()).unwrap();
+
+    // ポインタを乗せた有効なマスのプレビューは、実際に打ったときに返る石と同じ
+    let (x, y) = center(3, 1);
+    game.pointer_at("pointermove", "mouse", x, y).unwrap();
+    let hover = game.hover();
+    let preview = game.get_flip_preview(hover[0] as usize, hover[1] as usize);
+    let mut after = before.clone();
+    after.play(3, 1).unwrap();
+    let mut flipped = Vec::new();
+    for row in 0..8 {
+        for col in 0..8 {
+            if (row, col) != (3, 1) && before.board()[row][col] != after.board()[row][col] {
+                flipped.extend([row as i32, col as i32]);
+            }
+        }
+    }
+    assert!(flipped.len() > 2);
+    let mut squares: Vec<(i32, i32)> = preview.chunks(2).map(|square| (square[0], square[1])).collect();
+    squares.sort();
+    assert_eq!(squares.into_iter().flat_map(|(row, col)| [row, col]).collect::<Vec<_>>(), flipped);
+
+    // 無効なマス・石のあるマス・盤外は空
+    assert!(game.get_flip_preview(0, 0).is_empty());
+    assert!(game.get_flip_preview(3, 3).is_empty());
+    assert!(game.get_flip_preview(8, 0).is_empty());
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn heatmap_scores_match_a_shallow_search() {
+    let mut game = OthelloGame::headless();
+    game.set_ai_difficulty(AiDifficulty::Expert);
+    play_transcript(&mut game, "f5d6c3d3c4f4");
+    assert!(game.get_heatmap().is_empty());
+
+    // 上級の探索を深さ 3（HEATMAP_DEPTH）までに抑えた評価値
+    game.set_heatmap_enabled(true).unwrap();
+    let expected: Vec<i32> = Game::from_position(&game.export_position())
+        .unwrap()
+        .evaluate_moves_with(AiConfig::from(Strategy::AlphaBeta(3)))
+        .into_iter()
+        .flat_map(|((row, col), score)| [row as i32, col as i32, score])
+        .collect();
+    assert!(!expected.is_empty());
+    assert_eq!(game.get_heatmap(), expected);
+
+    game.set_heatmap_enabled(false).unwrap();
+    assert!(game.get_heatmap().is_empty());
+}
@@ -0,0 +1,61 @@
+import init, { review_transcript, search_position_with_config, SearchCancelToken } from './pkg/mini_wasm_othello.js';
+
+// AIの探索を行う Web Worker
+// メインスレッドから局面文字列と AI の設定文字列を受け取り、探索結果の手を返す。
+// 探索は非同期に少しずつ進むため、探索中でもキャンセルのメッセージを受け取れる。
+// 対局の振り返り（棋譜の各手の探索と終盤の完全読み）も受け付け、集計と評価値の推移を返す。
+
+const ready = init();
+let currentToken = null;
+
+self.addEventListener('message', async (event) => {
+    const message = event.data;
+    await ready;
+    
+    if (message.type === 'cancel') {
+        if (currentToken) {
+            currentToken.cancel();
+        }
+        return;
+    }
+    
+    if (message.type === 'review') {
+        try {
+            const report = review_transcript(message.transcript);
+            self.postMessage({
+                type: 'review',
+                id: message.id,
+                moveCount: report.move_count,
+                summary: report.summary(),
+                advantages: Array.from(report.advantages()),
+                probabilities: Array.from(report.win_probabilities()),
+            });
+            report.free();
+        } catch (error) {
+            self.postMessage({ type: 'error', id: message.id, error: String(error) });
+        }
+        return;
+    }
+    
+    if (message.type === 'search') {
+        // 新しい探索が来たら前の探索はキャンセル
+        if (currentToken) {
+            currentToken.cancel();
+        }
+        const token = new SearchCancelToken();
+        currentToken = token;
+        
+        try {
+            const move = await search_position_with_config(message.position, message.config, token);
+            self.postMessage({ type: 'result', id: message.id, move: Array.from(move) });
+        } catch (error) {
+            const type = token.cancelled ? 'cancelled' : 'error';
+            self.postMessage({ type, id: message.id, error: String(error) });
+        } finally {
+            if (currentToken === token) {
+                currentToken = null;
+            }
+            token.free();
+        }
+    }
+});